@@ -2,7 +2,7 @@
 
 use std::error::Error;
 
-use bluest::{Adapter, Uuid as BluestUUID};
+use bluest::{Adapter, L2CapChannelImpl, L2CapSecurity, SecurityLevel, Uuid as BluestUUID};
 use futures_lite::StreamExt;
 use tokio::io::AsyncReadExt;
 use tracing::info;
@@ -52,7 +52,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     adapter.connect_device(&device).await.unwrap();
 
-    let mut channel = device.open_l2cap_channel(PSM, true).await.unwrap();
+    let security = L2CapSecurity {
+        level: SecurityLevel::High,
+        key_size: 16,
+    };
+    let mut channel = device.open_l2cap_channel(PSM, security).await.unwrap();
+    info!(
+        "opened channel with send MTU {:?} and security {:?}",
+        channel.send_mtu(),
+        channel.security()
+    );
 
     info!("Reading from channel.");
     let mut hello_buf = [0u8; HELLO_MSG.len()];
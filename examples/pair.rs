@@ -98,8 +98,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
     adapter.connect_device(&device).await?;
     info!("connected!");
 
-    device.pair_with_agent(&StdioPairingAgent).await?;
-    info!("paired!");
+    let res = device.pair_with_agent(&StdioPairingAgent).await?;
+    info!("paired! {:?}", res);
 
     adapter.disconnect_device(&device).await?;
     info!("disconnected!");
@@ -1,14 +1,24 @@
 #![allow(clippy::let_unit_value)]
 
+use std::time::Duration;
+
 use futures_core::Stream;
+use futures_lite::future;
 
-use crate::{sys, AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceId, Result, Uuid};
+use crate::error::ErrorKind;
+use crate::util::sleep;
+use crate::{
+    sys, AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceEvent, DeviceId, Error, Result, ScanFilter,
+    ScanOptions, Uuid,
+};
+#[cfg(feature = "l2cap")]
+use crate::{L2CapListener, L2CapSecurity};
 
 /// The system's Bluetooth adapter interface.
 ///
 /// The default adapter for the system may be accessed with the [`Adapter::default()`] method.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Adapter(sys::adapter::AdapterImpl);
+pub struct Adapter(pub(crate) sys::adapter::AdapterImpl);
 
 impl Adapter {
     /// Creates an interface to the default Bluetooth adapter for the system
@@ -17,6 +27,175 @@ impl Adapter {
         sys::adapter::AdapterImpl::default().await.map(Adapter)
     }
 
+    /// Creates interfaces to all Bluetooth adapters of the system
+    ///
+    /// # Platform specifics
+    ///
+    /// On MacOS/iOS and Android, only the default adapter is returned.
+    #[inline]
+    pub async fn all() -> Result<Vec<Self>> {
+        Ok(sys::adapter::AdapterImpl::all()
+            .await?
+            .into_iter()
+            .map(Adapter)
+            .collect())
+    }
+
+    /// Creates an interface to the Bluetooth adapter with the given [`name`][Self::name]
+    ///
+    /// Returns an error of kind [`NotFound`][crate::error::ErrorKind::NotFound] if there is no such adapter.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub async fn by_name(name: &str) -> Result<Self> {
+        sys::adapter::AdapterImpl::by_name(name).await.map(Adapter)
+    }
+
+    /// Creates an interface to the Bluetooth adapter with the given [`address`][Self::address]
+    ///
+    /// Returns an error of kind [`NotFound`][crate::error::ErrorKind::NotFound] if there is no such adapter.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    pub async fn by_address(address: [u8; 6]) -> Result<Self> {
+        for adapter in Self::all().await? {
+            if adapter.address().await? == address {
+                return Ok(adapter);
+            }
+        }
+        Err(Error::new(
+            ErrorKind::NotFound,
+            None,
+            format!("no adapter with address {}", format_address(address)),
+        ))
+    }
+
+    /// The system name of this adapter, which uniquely identifies it
+    ///
+    /// # Platform specifics
+    ///
+    /// On Linux this is the name of the controller, e.g. `hci0`. On Windows this is the device id of the adapter.
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub fn name(&self) -> Result<String> {
+        self.0.name()
+    }
+
+    /// The Bluetooth address of this adapter, most significant byte first
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub async fn address(&self) -> Result<[u8; 6]> {
+        self.0.address().await
+    }
+
+    /// The human readable name of this adapter, which is shown to remote devices
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub async fn alias(&self) -> Result<String> {
+        self.0.alias().await
+    }
+
+    /// Whether this adapter is powered on
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Android.
+    #[inline]
+    pub async fn is_powered(&self) -> Result<bool> {
+        self.0.is_powered().await
+    }
+
+    /// Whether this adapter is discoverable by remote devices
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[inline]
+    pub async fn is_discoverable(&self) -> Result<bool> {
+        self.0.is_discoverable().await
+    }
+
+    /// Whether this adapter accepts pairing requests from remote devices
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[inline]
+    pub async fn is_pairable(&self) -> Result<bool> {
+        self.0.is_pairable().await
+    }
+
+    /// Powers this adapter on or off
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[inline]
+    pub async fn set_powered(&self, powered: bool) -> Result<()> {
+        self.0.set_powered(powered).await
+    }
+
+    /// Makes this adapter discoverable by remote devices, or stops it from being discoverable
+    ///
+    /// Discoverability is turned off again automatically after the
+    /// [discoverable timeout][Self::set_discoverable_timeout].
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[inline]
+    pub async fn set_discoverable(&self, discoverable: bool) -> Result<()> {
+        self.0.set_discoverable(discoverable).await
+    }
+
+    /// Sets how long this adapter stays discoverable after [`set_discoverable`][Self::set_discoverable] is called
+    ///
+    /// A timeout of zero keeps the adapter discoverable until it is turned off explicitly. The timeout has a
+    /// granularity of one second.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[inline]
+    pub async fn set_discoverable_timeout(&self, timeout: Duration) -> Result<()> {
+        self.0.set_discoverable_timeout(timeout).await
+    }
+
+    /// Allows or disallows pairing requests from remote devices
+    ///
+    /// Pairing is disallowed again automatically after the [pairable timeout][Self::set_pairable_timeout].
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[inline]
+    pub async fn set_pairable(&self, pairable: bool) -> Result<()> {
+        self.0.set_pairable(pairable).await
+    }
+
+    /// Sets how long this adapter stays pairable after [`set_pairable`][Self::set_pairable] is called
+    ///
+    /// A timeout of zero keeps the adapter pairable until it is turned off explicitly. The timeout has a granularity
+    /// of one second.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[inline]
+    pub async fn set_pairable_timeout(&self, timeout: Duration) -> Result<()> {
+        self.0.set_pairable_timeout(timeout).await
+    }
+
     /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
     #[inline]
     pub async fn events(&self) -> Result<impl Stream<Item = Result<AdapterEvent>> + Send + Unpin + '_> {
@@ -29,6 +208,22 @@ impl Adapter {
         self.0.wait_available().await
     }
 
+    /// Asynchronously blocks until the adapter is available or `timeout` has elapsed
+    ///
+    /// Returns an error of kind [`Timeout`][crate::error::ErrorKind::Timeout] if the adapter did not become available
+    /// in time, e.g. because it is blocked by rfkill.
+    pub async fn wait_available_timeout(&self, timeout: Duration) -> Result<()> {
+        let timeout = async {
+            sleep(timeout).await;
+            Err(Error::new(
+                ErrorKind::Timeout,
+                None,
+                "waiting for the adapter to become available".to_string(),
+            ))
+        };
+        future::or(self.wait_available(), timeout).await
+    }
+
     /// Attempts to create the device identified by `id`
     #[inline]
     pub async fn open_device(&self, id: &DeviceId) -> Result<Device> {
@@ -41,6 +236,33 @@ impl Adapter {
         self.0.connected_devices().await
     }
 
+    /// Finds all devices which are paired with the system
+    ///
+    /// Devices are returned without scanning, whether or not they are currently in range.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub async fn paired_devices(&self) -> Result<Vec<Device>> {
+        self.0.paired_devices().await
+    }
+
+    /// Finds all devices known to the system
+    ///
+    /// Besides paired and connected devices, this includes devices the system remembers from earlier scans.
+    /// Devices are returned without scanning, whether or not they are currently in range.
+    ///
+    /// # Platform specifics
+    ///
+    /// On Linux this includes all devices cached by BlueZ.
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    #[inline]
+    pub async fn known_devices(&self) -> Result<Vec<Device>> {
+        self.0.known_devices().await
+    }
+
     /// Finds all connected devices providing any service in `services`
     ///
     /// # Panics
@@ -67,6 +289,72 @@ impl Adapter {
         self.0.scan(services).await
     }
 
+    /// Starts scanning for Bluetooth advertising packets matching `filter`.
+    ///
+    /// Returns a stream of [`AdvertisingDevice`] structs which contain the data from the advertising packet and the
+    /// [`Device`] which sent it. Scanning is automatically stopped when the stream is dropped. Inclusion of duplicate
+    /// packets is a platform-specific implementation detail.
+    ///
+    /// Only advertisements matching every criterion of `filter` are returned. Criteria are passed to the platform
+    /// Bluetooth stack where it supports them, so that non-matching advertisements are discarded as early as possible.
+    /// The remaining criteria are checked in software.
+    ///
+    /// # Platform specific
+    ///
+    /// | Criterion          | MacOS/iOS | Windows | Linux  | Android |
+    /// | ------------------ | :-------: | :-----: | :----: | :-----: |
+    /// | `services`         |    ✅     |   ✅    |   ✅   |   ✅    |
+    /// | `local_name`       |    ❌     |  exact  | prefix |   ❌    |
+    /// | `min_rssi`         |    ❌     |   ✅    |   ✅   |   ❌    |
+    /// | all other criteria |    ❌     |   ❌    |   ❌   |   ❌    |
+    ///
+    /// ✅ = applied by the OS\
+    /// ❌ = applied in software only
+    #[inline]
+    pub async fn scan_with_filter(
+        &self,
+        filter: ScanFilter,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        self.0.scan_with_filter(filter).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter` using the given `options`.
+    ///
+    /// Behaves like [`scan_with_filter`][Self::scan_with_filter], but allows choosing between active and passive
+    /// scanning, how duplicate advertisements are reported and which transports are scanned.
+    ///
+    /// # Platform specific
+    ///
+    /// | Option                           | MacOS/iOS | Windows | Linux | Android |
+    /// | -------------------------------- | :-------: | :-----: | :---: | :-----: |
+    /// | `ScanMode::Passive`              |    ❌     |   ✅    |  ⚠️   |   ❌    |
+    /// | `DuplicatePolicy::ReportAll`     |    ✅     |   ✅    |  ✅   |   ✅    |
+    /// | `DuplicatePolicy::ReportChanges` |    ⚠️     |   ⚠️    |  ✅   |   ⚠️    |
+    /// | `ScanTransport::Dual`            |    ⚠️     |   ⚠️    |  ✅   |   ⚠️    |
+    /// | `max_pathloss`                   |    ⚠️     |   ⚠️    |  ✅   |   ⚠️    |
+    ///
+    /// ✅ = supported by the OS\
+    /// ⚠️ = applied in software, or for `ScanTransport::Dual`, only LE devices are discovered, or for
+    /// `ScanMode::Passive`, see below\
+    /// ❌ = returns an error of kind [`NotSupported`][crate::error::ErrorKind::NotSupported]
+    ///
+    /// On Linux, passive scans use BlueZ's advertisement monitor API, which older BlueZ versions only provide when
+    /// `bluetoothd` is started with `--experimental`. The monitor only matches advertisements against patterns, so
+    /// `filter` must match on manufacturer data, service data, services or the local name, otherwise an error of kind
+    /// [`NotSupported`][crate::error::ErrorKind::NotSupported] is returned. A device is reported when it is first found
+    /// and again only after BlueZ has lost track of it.
+    ///
+    /// On Linux, BlueZ keeps a single discovery filter per client, so while several scans are running the union of
+    /// their filters is applied. Each scan still only returns the advertisements matching its own `filter`.
+    #[inline]
+    pub async fn scan_with_options(
+        &self,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        self.0.scan_with_options(filter, options).await
+    }
+
     /// Finds Bluetooth devices providing any service in `services`.
     ///
     /// Returns a stream of [`Device`] structs with matching connected devices returned first. If the stream is not
@@ -149,4 +437,47 @@ impl Adapter {
     ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
         self.0.device_connection_events(device).await
     }
+
+    /// Monitors all devices known to the adapter for changes.
+    ///
+    /// Reports devices being added to or removed from the adapter, connecting or disconnecting, being paired or
+    /// unpaired, and changing their name, without requiring a stream per device.
+    ///
+    /// # Platform specifics
+    ///
+    /// | Event                      | MacOS/iOS | Windows | Linux | Android |
+    /// | -------------------------- | :-------: | :-----: | :---: | :-----: |
+    /// | `Added`/`Removed`          |    ❌     |   ❌    |  ✅   |   ❌    |
+    /// | `Connected`/`Disconnected` |    ⚠️     |   ❌    |  ✅   |   ❌    |
+    /// | `Paired`/`Unpaired`        |    ❌     |   ❌    |  ✅   |   ❌    |
+    /// | `NameChanged`              |    ❌     |   ❌    |  ✅   |   ❌    |
+    ///
+    /// ✅ = supported\
+    /// ⚠️ = only reported for devices connected with [`connect_device`][Self::connect_device]\
+    /// ❌ = not reported
+    ///
+    /// On Windows and Android an error of kind [`NotSupported`][crate::error::ErrorKind::NotSupported] is returned.
+    #[inline]
+    pub async fn device_events(&self) -> Result<impl Stream<Item = Result<DeviceEvent>> + Send + Unpin + '_> {
+        self.0.device_events().await
+    }
+
+    /// Listen for L2CAP connection-oriented channels (CoC) opened by remote devices.
+    ///
+    /// If `psm` is `None`, a PSM from the dynamic range is assigned by the system. It can be retrieved with
+    /// [`L2CapListener::psm`] to share it with peers, e.g. through a GATT characteristic. Only channels meeting the
+    /// requested `security` are accepted.
+    ///
+    /// # Platform specifics
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[cfg(feature = "l2cap")]
+    #[inline]
+    pub async fn listen_l2cap(&self, psm: Option<u16>, security: L2CapSecurity) -> Result<L2CapListener> {
+        self.0.listen_l2cap(psm, security).await.map(L2CapListener)
+    }
+}
+
+fn format_address(address: [u8; 6]) -> String {
+    address.map(|x| format!("{x:02X}")).join(":")
 }
@@ -0,0 +1,247 @@
+//! Parsing of raw advertising data as defined in the Bluetooth Core Specification, Vol 3, Part C, §11 and the Core
+//! Specification Supplement (CSS), Part A.
+
+use crate::error::ErrorKind;
+use crate::{AdvertisementData, BluetoothUuidExt, Error, ManufacturerData, Result, Uuid};
+
+const FLAGS: u8 = 0x01;
+pub(crate) const INCOMPLETE_LIST_16_BIT_UUIDS: u8 = 0x02;
+pub(crate) const COMPLETE_LIST_16_BIT_UUIDS: u8 = 0x03;
+pub(crate) const INCOMPLETE_LIST_32_BIT_UUIDS: u8 = 0x04;
+pub(crate) const COMPLETE_LIST_32_BIT_UUIDS: u8 = 0x05;
+pub(crate) const INCOMPLETE_LIST_128_BIT_UUIDS: u8 = 0x06;
+pub(crate) const COMPLETE_LIST_128_BIT_UUIDS: u8 = 0x07;
+pub(crate) const SHORTENED_LOCAL_NAME: u8 = 0x08;
+pub(crate) const COMPLETE_LOCAL_NAME: u8 = 0x09;
+const TX_POWER_LEVEL: u8 = 0x0a;
+const SOLICITATION_16_BIT_UUIDS: u8 = 0x14;
+const SOLICITATION_128_BIT_UUIDS: u8 = 0x15;
+pub(crate) const SERVICE_DATA_16_BIT_UUID: u8 = 0x16;
+const APPEARANCE: u8 = 0x19;
+const SOLICITATION_32_BIT_UUIDS: u8 = 0x1f;
+pub(crate) const SERVICE_DATA_32_BIT_UUID: u8 = 0x20;
+pub(crate) const SERVICE_DATA_128_BIT_UUID: u8 = 0x21;
+const URI: u8 = 0x24;
+const LE_SUPPORTED_FEATURES: u8 = 0x27;
+pub(crate) const MANUFACTURER_SPECIFIC_DATA: u8 = 0xff;
+
+impl AdvertisementData {
+    /// Parses the raw payload of an advertising or scan response packet.
+    ///
+    /// `data` must be a sequence of AD structures as defined in the Bluetooth Core Specification, Vol 3, Part C, §11.
+    /// A zero length AD structure ends the significant part of the payload and any remaining bytes are ignored. AD
+    /// types which are not represented by a field of [`AdvertisementData`] are preserved in
+    /// [`unknown_data`][AdvertisementData::unknown_data].
+    ///
+    /// The advertising PDU type is not part of the payload, so [`is_connectable`][AdvertisementData::is_connectable]
+    /// is always `false` in the result.
+    ///
+    /// Returns an error with a [`kind`][Error::kind] of [`InvalidParameter`][ErrorKind::InvalidParameter] if `data`
+    /// is malformed.
+    pub fn from_raw(data: &[u8]) -> Result<Self> {
+        let mut res = AdvertisementData::default();
+        let mut rest = data;
+        while let Some((&len, tail)) = rest.split_first() {
+            if len == 0 {
+                break;
+            }
+
+            let len = usize::from(len);
+            if tail.len() < len {
+                return Err(invalid(format!(
+                    "AD structure length {len} exceeds the remaining {} bytes",
+                    tail.len()
+                )));
+            }
+
+            res.parse_ad_structure(tail[0], &tail[1..len])?;
+            rest = &tail[len..];
+        }
+
+        Ok(res)
+    }
+
+    /// Merges `other` into this advertisement, e.g. to combine an advertisement with its scan response.
+    ///
+    /// UUID lists, manufacturer specific data and service data are combined. Where both advertisements contain the
+    /// same single-valued field, the value from `other` is used, except that a complete local name is never replaced by
+    /// a shortened one.
+    pub fn merge(&mut self, other: AdvertisementData) {
+        let self_complete = self.local_name.is_some() && self.local_name != self.shortened_local_name;
+        let other_complete = other.local_name.is_some() && other.local_name != other.shortened_local_name;
+        if other.local_name.is_some() && (other_complete || !self_complete) {
+            self.local_name = other.local_name;
+        }
+        self.shortened_local_name = other.shortened_local_name.or(self.shortened_local_name.take());
+
+        for data in other.all_manufacturer_data {
+            if !self.all_manufacturer_data.contains(&data) {
+                self.all_manufacturer_data.push(data);
+            }
+        }
+        if self.manufacturer_data.is_none() {
+            self.manufacturer_data = other.manufacturer_data;
+        }
+
+        for uuid in other.services {
+            push_unique(&mut self.services, uuid);
+        }
+        for uuid in other.solicited_services {
+            push_unique(&mut self.solicited_services, uuid);
+        }
+        self.service_data.extend(other.service_data);
+
+        self.tx_power_level = other.tx_power_level.or(self.tx_power_level);
+        self.flags = other.flags.or(self.flags);
+        self.appearance = other.appearance.or(self.appearance);
+        self.uri = other.uri.or(self.uri.take());
+        self.le_supported_features = other.le_supported_features.or(self.le_supported_features.take());
+        self.unknown_data.extend(other.unknown_data);
+        self.is_connectable |= other.is_connectable;
+    }
+
+    fn parse_ad_structure(&mut self, ad_type: u8, value: &[u8]) -> Result<()> {
+        match ad_type {
+            FLAGS => {
+                let flags = value.first().ok_or_else(|| invalid("empty flags".to_string()))?;
+                self.flags = Some(*flags);
+            }
+            INCOMPLETE_LIST_16_BIT_UUIDS | COMPLETE_LIST_16_BIT_UUIDS => {
+                for uuid in parse_uuids(value, 2)? {
+                    push_unique(&mut self.services, uuid);
+                }
+            }
+            INCOMPLETE_LIST_32_BIT_UUIDS | COMPLETE_LIST_32_BIT_UUIDS => {
+                for uuid in parse_uuids(value, 4)? {
+                    push_unique(&mut self.services, uuid);
+                }
+            }
+            INCOMPLETE_LIST_128_BIT_UUIDS | COMPLETE_LIST_128_BIT_UUIDS => {
+                for uuid in parse_uuids(value, 16)? {
+                    push_unique(&mut self.services, uuid);
+                }
+            }
+            SHORTENED_LOCAL_NAME => {
+                let name = String::from_utf8_lossy(value).into_owned();
+                if self.local_name.is_none() {
+                    self.local_name = Some(name.clone());
+                }
+                self.shortened_local_name = Some(name);
+            }
+            COMPLETE_LOCAL_NAME => {
+                self.local_name = Some(String::from_utf8_lossy(value).into_owned());
+            }
+            TX_POWER_LEVEL => match value {
+                [level] => self.tx_power_level = Some(i16::from(*level as i8)),
+                _ => return Err(invalid(format!("invalid TX power level length {}", value.len()))),
+            },
+            SOLICITATION_16_BIT_UUIDS => {
+                for uuid in parse_uuids(value, 2)? {
+                    push_unique(&mut self.solicited_services, uuid);
+                }
+            }
+            SOLICITATION_32_BIT_UUIDS => {
+                for uuid in parse_uuids(value, 4)? {
+                    push_unique(&mut self.solicited_services, uuid);
+                }
+            }
+            SOLICITATION_128_BIT_UUIDS => {
+                for uuid in parse_uuids(value, 16)? {
+                    push_unique(&mut self.solicited_services, uuid);
+                }
+            }
+            SERVICE_DATA_16_BIT_UUID => self.parse_service_data(value, 2)?,
+            SERVICE_DATA_32_BIT_UUID => self.parse_service_data(value, 4)?,
+            SERVICE_DATA_128_BIT_UUID => self.parse_service_data(value, 16)?,
+            APPEARANCE => match value {
+                [lo, hi] => self.appearance = Some(u16::from_le_bytes([*lo, *hi])),
+                _ => return Err(invalid(format!("invalid appearance length {}", value.len()))),
+            },
+            URI => {
+                let uri = std::str::from_utf8(value).map_err(|err| {
+                    Error::new(
+                        ErrorKind::InvalidParameter,
+                        Some(Box::new(err)),
+                        "invalid URI".to_string(),
+                    )
+                })?;
+                let mut chars = uri.chars();
+                let scheme = chars.next().ok_or_else(|| invalid("empty URI".to_string()))?;
+                match uri_scheme(scheme) {
+                    Some(scheme) => self.uri = Some(format!("{scheme}{}", chars.as_str())),
+                    None => self.unknown_data.push((ad_type, value.to_vec())),
+                }
+            }
+            LE_SUPPORTED_FEATURES => self.le_supported_features = Some(value.to_vec()),
+            MANUFACTURER_SPECIFIC_DATA => {
+                if value.len() < 2 {
+                    return Err(invalid(
+                        "manufacturer specific data is missing the company identifier".to_string(),
+                    ));
+                }
+                let data = ManufacturerData {
+                    company_id: u16::from_le_bytes([value[0], value[1]]),
+                    data: value[2..].to_vec(),
+                };
+                if self.manufacturer_data.is_none() {
+                    self.manufacturer_data = Some(data.clone());
+                }
+                self.all_manufacturer_data.push(data);
+            }
+            _ => self.unknown_data.push((ad_type, value.to_vec())),
+        }
+
+        Ok(())
+    }
+
+    fn parse_service_data(&mut self, value: &[u8], uuid_len: usize) -> Result<()> {
+        if value.len() < uuid_len {
+            return Err(invalid(format!(
+                "service data is shorter than its {}-bit UUID",
+                uuid_len * 8
+            )));
+        }
+        let (uuid, data) = value.split_at(uuid_len);
+        self.service_data.insert(uuid_from_le_bytes(uuid), data.to_vec());
+        Ok(())
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidParameter, None, message)
+}
+
+fn push_unique(uuids: &mut Vec<Uuid>, uuid: Uuid) {
+    if !uuids.contains(&uuid) {
+        uuids.push(uuid);
+    }
+}
+
+fn parse_uuids(value: &[u8], uuid_len: usize) -> Result<impl Iterator<Item = Uuid> + '_> {
+    let chunks = value.chunks_exact(uuid_len);
+    if !chunks.remainder().is_empty() {
+        return Err(invalid(format!(
+            "UUID list length {} is not a multiple of {uuid_len}",
+            value.len()
+        )));
+    }
+    Ok(chunks.map(uuid_from_le_bytes))
+}
+
+/// AD structures encode UUIDs in little-endian byte order
+fn uuid_from_le_bytes(bytes: &[u8]) -> Uuid {
+    let mut bytes = bytes.to_vec();
+    bytes.reverse();
+    Uuid::from_bluetooth_bytes(&bytes)
+}
+
+/// Looks up the URI scheme name string for `code` (Bluetooth Assigned Numbers §2.7)
+fn uri_scheme(code: char) -> Option<&'static str> {
+    const SCHEMES: [&str; 23] = [
+        "", "aaa:", "aaas:", "about:", "acap:", "acct:", "cap:", "cid:", "coap:", "coaps:", "crid:", "data:", "dav:",
+        "dict:", "dns:", "file:", "ftp:", "geo:", "go:", "gopher:", "h323:", "http:", "https:",
+    ];
+
+    let index = usize::try_from(u32::from(code)).ok()?.checked_sub(1)?;
+    SCHEMES.get(index).copied()
+}
@@ -1,18 +1,20 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
-use bluedroid::{
-    scan::{
-        scan_filter::{ScanFilter, ServiceUuid},
-        scan_result::ScanResult,
-    },
-    ConnectionState,
-};
+use bluedroid::scan::scan_filter::{ScanFilter, ServiceUuid};
+use bluedroid::scan::scan_result::ScanResult;
+use bluedroid::ConnectionState;
 use futures_lite::{stream, Stream, StreamExt};
 use tracing::{error, trace};
 use uuid::Uuid;
 
-use super::{device::DeviceImpl, DeviceId};
-use crate::{AdapterEvent, AdvertisementData, AdvertisingDevice, ConnectionEvent, Device, ManufacturerData, Result};
+use super::device::DeviceImpl;
+use super::DeviceId;
+use crate::error::ErrorKind;
+use crate::{
+    AdapterEvent, AdvertisementData, AdvertisingDevice, ConnectionEvent, Device, DeviceEvent, DisconnectReason,
+    ManufacturerData, Result, ScanOptions,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AdapterImpl(bluedroid::Adapter);
@@ -23,6 +25,97 @@ impl AdapterImpl {
         Some(Self(bluedroid::Adapter::default()))
     }
 
+    /// Creates interfaces to all Bluetooth adapters of the system
+    ///
+    /// Only the default adapter is available on Android.
+    pub async fn all() -> Result<Vec<Self>> {
+        Ok(Self::default().await.into_iter().collect())
+    }
+
+    /// Creates an interface to the Bluetooth adapter with the given name
+    ///
+    /// Not supported on Android.
+    pub async fn by_name(_name: &str) -> Result<Self> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The system name of this adapter, which uniquely identifies it
+    ///
+    /// Not supported on Android.
+    pub fn name(&self) -> Result<String> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The Bluetooth address of this adapter, most significant byte first
+    ///
+    /// Not supported on Android.
+    pub async fn address(&self) -> Result<[u8; 6]> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The human readable name of this adapter, which is shown to remote devices
+    ///
+    /// Not supported on Android.
+    pub async fn alias(&self) -> Result<String> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether this adapter is powered on
+    ///
+    /// Not supported on Android.
+    pub async fn is_powered(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether this adapter is discoverable by remote devices
+    ///
+    /// Not supported on Android.
+    pub async fn is_discoverable(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether this adapter accepts pairing requests from remote devices
+    ///
+    /// Not supported on Android.
+    pub async fn is_pairable(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Powers this adapter on or off
+    ///
+    /// Not supported on Android.
+    pub async fn set_powered(&self, _powered: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Makes this adapter discoverable by remote devices, or stops it from being discoverable
+    ///
+    /// Not supported on Android.
+    pub async fn set_discoverable(&self, _discoverable: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Sets how long this adapter stays discoverable after `set_discoverable` is called
+    ///
+    /// Not supported on Android.
+    pub async fn set_discoverable_timeout(&self, _timeout: Duration) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Allows or disallows pairing requests from remote devices
+    ///
+    /// Not supported on Android.
+    pub async fn set_pairable(&self, _pairable: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Sets how long this adapter stays pairable after `set_pairable` is called
+    ///
+    /// Not supported on Android.
+    pub async fn set_pairable_timeout(&self, _timeout: Duration) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
     pub(crate) async fn events(&self) -> Result<impl Stream<Item = Result<AdapterEvent>> + Send + Unpin + '_> {
         Ok(stream::empty()) // TODO
@@ -50,6 +143,20 @@ impl AdapterImpl {
             .collect())
     }
 
+    /// Finds all devices which are paired with the system
+    ///
+    /// Not supported on Android.
+    pub async fn paired_devices(&self) -> Result<Vec<Device>> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Finds all devices known to the system
+    ///
+    /// Not supported on Android.
+    pub async fn known_devices(&self) -> Result<Vec<Device>> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Finds all connected devices providing any service in `services`
     ///
     /// # Panics
@@ -80,14 +187,35 @@ impl AdapterImpl {
     ///
     /// If `services` is not empty, returns advertisements including at least one GATT service with a UUID in
     /// `services`. Otherwise returns all advertisements.
-    ///
-    /// Android seems to support having multiple manufacturer data elements. Panic if there is more then one.
-    /// https://developer.android.com/reference/android/bluetooth/le/ScanRecord#getManufacturerSpecificData()
     pub async fn scan<'a>(
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
-        let scan = self.0.scan(get_filters(services))?;
+        self.scan_with_filter(crate::ScanFilter::with_services(services)).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter`.
+    ///
+    /// The services of `filter` are applied by the Android scanner. All other criteria are checked in software.
+    pub async fn scan_with_filter(
+        &self,
+        filter: crate::ScanFilter,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        self.scan_with_options(filter, ScanOptions::default()).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter` using the given `options`.
+    ///
+    /// Passive scanning is not supported. [`DuplicatePolicy::ReportChanges`][crate::DuplicatePolicy::ReportChanges]
+    /// and the maximum path loss are applied in software. Only Bluetooth LE devices are discovered.
+    pub async fn scan_with_options(
+        &self,
+        filter: crate::ScanFilter,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        options.check_mode(false)?;
+
+        let scan = self.0.scan(get_filters(&filter.services))?;
         Ok(scan
             .take_while(|scan_result| {
                 if let Err(e) = scan_result {
@@ -100,25 +228,22 @@ impl AdapterImpl {
                 if !check_advertising(&service) {
                     return None;
                 }
+                let all_manufacturer_data: Vec<ManufacturerData> = service
+                    .manufacturer_specific_data()
+                    .into_iter()
+                    .map(|(company_id, data)| ManufacturerData {
+                        company_id: company_id.try_into().expect("Invalid company id"),
+                        data: data.into_vec(),
+                    })
+                    .collect();
                 Some(AdvertisingDevice {
                     device: Device(DeviceImpl {
                         device: service.device(),
                     }),
                     adv_data: AdvertisementData {
                         local_name: service.local_name(),
-                        manufacturer_data: {
-                            let mut manufacturer_datas = service.manufacturer_specific_data().into_iter();
-                            let manufacturer_data =
-                                manufacturer_datas.next().map(|(company_id, data)| ManufacturerData {
-                                    company_id: company_id.try_into().expect("Invalid company id"),
-                                    data: data.into_vec(),
-                                });
-                            assert!(
-                                manufacturer_datas.next().is_none(),
-                                "There should at most one manufacturer data entry"
-                            );
-                            manufacturer_data
-                        },
+                        manufacturer_data: all_manufacturer_data.first().cloned(),
+                        all_manufacturer_data,
                         services: service.get_service_uuids(),
                         service_data: HashMap::from_iter(
                             service
@@ -128,10 +253,13 @@ impl AdapterImpl {
                         ),
                         tx_power_level: service.tx_power_level().map(i16::from),
                         is_connectable: service.is_connectable(),
+                        ..Default::default()
                     },
                     rssi: Some(i16::from(service.rssi())),
                 })
-            }))
+            })
+            .filter(move |x| filter.matches(x) && options.matches(x))
+            .filter(options.duplicate_filter()))
     }
 
     /// Finds Bluetooth devices providing any service in `services`.
@@ -189,11 +317,33 @@ impl AdapterImpl {
                 let connection_state = connection_state.expect("Errors excluded by take_while");
                 match connection_state {
                     ConnectionState::Connected => Some(ConnectionEvent::Connected),
-                    ConnectionState::Disconnected => Some(ConnectionEvent::Disconnected),
-                    ConnectionState::Disconnecting | ConnectionState::Connecting => None,
+                    ConnectionState::Disconnected => Some(ConnectionEvent::Disconnected {
+                        reason: DisconnectReason::Unknown,
+                    }),
+                    ConnectionState::Connecting => Some(ConnectionEvent::Connecting),
+                    ConnectionState::Disconnecting => Some(ConnectionEvent::Disconnecting),
                 }
             }))
     }
+
+    /// Monitors all devices known to the adapter for changes.
+    ///
+    /// Not supported on Android.
+    pub async fn device_events(&self) -> Result<impl Stream<Item = Result<DeviceEvent>> + Send + Unpin + '_> {
+        Err::<stream::Empty<_>, _>(ErrorKind::NotSupported.into())
+    }
+
+    /// Listen for L2CAP connection-oriented channels opened by remote devices
+    ///
+    /// Not supported on Android.
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(
+        &self,
+        _psm: Option<u16>,
+        _security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::Listener> {
+        Err(ErrorKind::NotSupported.into())
+    }
 }
 
 fn get_filters(services: &[Uuid]) -> Vec<ScanFilter> {
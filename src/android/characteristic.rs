@@ -71,6 +71,11 @@ impl CharacteristicImpl {
     }
 
     pub async fn notify(&self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + '_> {
+        self.clone().notify_owned().await
+    }
+
+    /// Like [`notify`][Self::notify], but the returned stream owns the characteristic.
+    pub async fn notify_owned(self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + 'static> {
         Ok(self.characteristic.notify().await?.map(|data| Ok(data.into_vec())))
     }
 
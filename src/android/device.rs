@@ -1,12 +1,11 @@
+use bluedroid::ConnectionState;
 use futures_core::Stream;
-use futures_lite::StreamExt;
+use futures_lite::{stream, StreamExt};
 use uuid::Uuid;
 
-use bluedroid::ConnectionState;
-
 use crate::error::ErrorKind;
-use crate::pairing::PairingAgent;
-use crate::{DeviceId, Result, Service, ServicesChanged};
+use crate::pairing::{PairingAgent, PairingResult};
+use crate::{DeviceId, DeviceProperty, Result, SecurityLevel, Service, ServicesChanged};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DeviceImpl {
@@ -35,6 +34,10 @@ impl DeviceImpl {
         self.name()
     }
 
+    pub async fn alias(&self) -> Result<String> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     pub async fn is_connected(&self) -> bool {
         // Use the client connection state as you need that for the device to be usable
         // Devices can be connected globally, but you still need to call connect for them
@@ -46,11 +49,17 @@ impl DeviceImpl {
         Ok(self.device.paired())
     }
 
-    pub async fn pair(&self) -> Result<()> {
-        Ok(self.device.pair().await?)
+    pub async fn pair(&self) -> Result<PairingResult> {
+        self.device.pair().await?;
+        // `BluetoothDevice.createBond` always bonds
+        Ok(PairingResult {
+            bonded: true,
+            method: None,
+            security_level: None,
+        })
     }
 
-    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, _agent: &T) -> Result<()> {
+    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, _agent: &T) -> Result<PairingResult> {
         unimplemented!("Android does not support pairing Agents")
     }
 
@@ -58,6 +67,20 @@ impl DeviceImpl {
         unimplemented!("Android does not support unpairing")
     }
 
+    /// The security level of the current connection to this device
+    ///
+    /// Not supported on Android.
+    pub async fn security_level(&self) -> Result<SecurityLevel> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether the current connection to this device is encrypted
+    ///
+    /// Not supported on Android.
+    pub async fn is_encrypted(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     pub async fn discover_services(&self) -> Result<Vec<Service>> {
         Ok(self
             .device
@@ -103,14 +126,40 @@ impl DeviceImpl {
             .map(|()| Ok(ServicesChanged(ServicesChangedImpl))))
     }
 
+    pub async fn property_changes(&self) -> Result<impl Stream<Item = DeviceProperty> + Send + Unpin + '_> {
+        Err::<stream::Empty<_>, _>(ErrorKind::NotSupported.into())
+    }
+
     pub async fn rssi(&self) -> Result<i16> {
         Ok(self.device.rssi().await?.try_into().unwrap())
     }
 
+    pub async fn refresh_rssi(&self) -> Result<i16> {
+        self.rssi().await
+    }
+
     #[cfg(feature = "l2cap")]
-    pub async fn open_l2cap_channel(&self, psm: u16, secure: bool) -> Result<crate::L2CapChannel> {
+    pub async fn open_l2cap_channel(&self, psm: u16, security: crate::L2CapSecurity) -> Result<crate::L2CapChannel> {
         use super::l2cap_channel::Channel;
-        Ok(Channel::new(self.device.open_l2cap_channel(psm, secure)?))
+        // Android only distinguishes between insecure channels and encrypted, authenticated ones
+        let secure = security.level > crate::SecurityLevel::Low;
+        Ok(Channel::new(
+            self.device.open_l2cap_channel(psm, secure)?,
+            psm,
+            self.id(),
+        ))
+    }
+
+    /// Open an L2CAP connection-oriented channel in sequential packet mode
+    ///
+    /// Not supported on Android.
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_packet_channel(
+        &self,
+        _psm: u16,
+        _security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::PacketChannel> {
+        Err(ErrorKind::NotSupported.into())
     }
 }
 
@@ -1,35 +1,61 @@
-use std::{
-    pin::{pin, Pin},
-    task::{Context, Poll},
-};
+use std::pin::{pin, Pin};
+use std::task::{Context, Poll};
 
+use bluedroid::l2cap_channel::{Channel as RawChannel, Reader as RawReader, Writer as RawWriter};
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
-
 use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt, FuturesAsyncWriteCompatExt};
 
-use bluedroid::l2cap_channel::{Channel as RawChannel, Reader as RawReader, Writer as RawWriter};
-
-use crate::L2CapChannelImpl;
+use super::DeviceId;
+use crate::error::ErrorKind;
+use crate::{L2CapChannelImpl, L2CapFlowControl, L2CapSecurity};
 
 pub struct Channel {
     reader: Compat<RawReader>,
     writer: Compat<RawWriter>,
+    psm: u16,
+    peer: DeviceId,
 }
 
 impl Channel {
-    pub(crate) fn new(channel: RawChannel) -> Self {
+    pub(crate) fn new(channel: RawChannel, psm: u16, peer: DeviceId) -> Self {
         let (reader, writer) = channel.split();
         Self {
             reader: reader.compat(),
             writer: writer.compat_write(),
+            psm,
+            peer,
         }
     }
 }
 
 impl L2CapChannelImpl for Channel {
-    fn split(self) -> (Reader, Writer) {
-        let Self { reader, writer } = self;
-        (reader, writer)
+    fn split(self) -> (crate::L2CapReader, crate::L2CapWriter) {
+        let Self { reader, writer, .. } = self;
+        (crate::L2CapReader(reader), crate::L2CapWriter(writer))
+    }
+
+    fn psm(&self) -> crate::Result<u16> {
+        Ok(self.psm)
+    }
+
+    fn peer(&self) -> crate::Result<DeviceId> {
+        Ok(self.peer.clone())
+    }
+
+    fn send_mtu(&self) -> crate::Result<usize> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn recv_mtu(&self) -> crate::Result<usize> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn security(&self) -> crate::Result<L2CapSecurity> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn flow_control(&self) -> crate::Result<L2CapFlowControl> {
+        Err(ErrorKind::NotSupported.into())
     }
 }
 
@@ -55,3 +81,39 @@ impl AsyncWrite for Channel {
 
 pub type Reader = Compat<RawReader>;
 pub type Writer = Compat<RawWriter>;
+
+/// Listening for L2CAP channels is not supported on Android, so no listener is ever created.
+#[derive(Debug)]
+pub enum Listener {}
+
+impl Listener {
+    pub fn psm(&self) -> u16 {
+        match *self {}
+    }
+
+    pub async fn accept(&self) -> crate::Result<(Channel, crate::Device)> {
+        match *self {}
+    }
+}
+
+/// Sequential packet mode is not supported on Android, so no packet channel is ever created.
+#[derive(Debug)]
+pub enum PacketChannel {}
+
+impl PacketChannel {
+    pub async fn send(&self, _packet: &[u8]) -> crate::Result<()> {
+        match *self {}
+    }
+
+    pub async fn recv(&self) -> crate::Result<Vec<u8>> {
+        match *self {}
+    }
+
+    pub fn send_mtu(&self) -> crate::Result<usize> {
+        match *self {}
+    }
+
+    pub fn recv_mtu(&self) -> crate::Result<usize> {
+        match *self {}
+    }
+}
@@ -7,7 +7,9 @@ pub mod service;
 #[cfg(feature = "l2cap")]
 pub mod l2cap_channel;
 
+mod discovery;
 mod error;
+mod monitor;
 
 /// A platform-specific device identifier.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
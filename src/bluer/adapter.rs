@@ -1,11 +1,21 @@
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
 
+use bluer::monitor::{Monitor, MonitorEvent};
 use bluer::AdapterProperty;
 use futures_core::Stream;
-use futures_lite::StreamExt;
+use futures_lite::{future, stream, StreamExt};
+use tracing::debug;
 
+use super::discovery::DiscoveryFilters;
 use crate::error::ErrorKind;
-use crate::{AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceId, Error, Result, Uuid};
+use crate::{
+    AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceEvent, DeviceId, DisconnectReason, DuplicatePolicy,
+    Error, LocalNameFilter, Result, ScanFilter, ScanMode, ScanOptions, ScanTransport, Uuid,
+};
 
 /// The system's Bluetooth adapter interface.
 ///
@@ -14,6 +24,7 @@ use crate::{AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceId,
 pub struct AdapterImpl {
     inner: bluer::Adapter,
     session: Arc<bluer::Session>,
+    discovery_filters: Arc<Mutex<DiscoveryFilters>>,
 }
 
 impl PartialEq for AdapterImpl {
@@ -38,7 +49,99 @@ impl AdapterImpl {
             .default_adapter()
             .await
             .ok()
-            .map(|inner| AdapterImpl { inner, session })
+            .map(|inner| AdapterImpl::new(inner, session))
+    }
+
+    /// Creates interfaces to all Bluetooth adapters of the system
+    pub async fn all() -> Result<Vec<Self>> {
+        let session = Arc::new(bluer::Session::new().await?);
+        let mut adapters = Vec::new();
+        for name in session.adapter_names().await? {
+            adapters.push(AdapterImpl::new(session.adapter(&name)?, session.clone()));
+        }
+        Ok(adapters)
+    }
+
+    /// Creates an interface to the Bluetooth adapter with the given name
+    pub async fn by_name(name: &str) -> Result<Self> {
+        let session = Arc::new(bluer::Session::new().await?);
+        if !session.adapter_names().await?.iter().any(|x| x == name) {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                None,
+                format!("no adapter named {name}"),
+            ));
+        }
+        Ok(AdapterImpl::new(session.adapter(name)?, session))
+    }
+
+    fn new(inner: bluer::Adapter, session: Arc<bluer::Session>) -> Self {
+        AdapterImpl {
+            inner,
+            session,
+            discovery_filters: Default::default(),
+        }
+    }
+
+    /// The system name of this adapter, which uniquely identifies it
+    pub fn name(&self) -> Result<String> {
+        Ok(self.inner.name().to_string())
+    }
+
+    /// The Bluetooth address of this adapter, most significant byte first
+    pub async fn address(&self) -> Result<[u8; 6]> {
+        Ok(self.inner.address().await?.0)
+    }
+
+    /// The human readable name of this adapter, which is shown to remote devices
+    pub async fn alias(&self) -> Result<String> {
+        self.inner.alias().await.map_err(Into::into)
+    }
+
+    /// Whether this adapter is powered on
+    pub async fn is_powered(&self) -> Result<bool> {
+        self.inner.is_powered().await.map_err(Into::into)
+    }
+
+    /// Whether this adapter is discoverable by remote devices
+    pub async fn is_discoverable(&self) -> Result<bool> {
+        self.inner.is_discoverable().await.map_err(Into::into)
+    }
+
+    /// Whether this adapter accepts pairing requests from remote devices
+    pub async fn is_pairable(&self) -> Result<bool> {
+        self.inner.is_pairable().await.map_err(Into::into)
+    }
+
+    /// Powers this adapter on or off
+    pub async fn set_powered(&self, powered: bool) -> Result<()> {
+        self.inner.set_powered(powered).await.map_err(Into::into)
+    }
+
+    /// Makes this adapter discoverable by remote devices, or stops it from being discoverable
+    pub async fn set_discoverable(&self, discoverable: bool) -> Result<()> {
+        self.inner.set_discoverable(discoverable).await.map_err(Into::into)
+    }
+
+    /// Sets how long this adapter stays discoverable after `set_discoverable` is called
+    pub async fn set_discoverable_timeout(&self, timeout: Duration) -> Result<()> {
+        self.inner
+            .set_discoverable_timeout(timeout_secs(timeout))
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Allows or disallows pairing requests from remote devices
+    pub async fn set_pairable(&self, pairable: bool) -> Result<()> {
+        self.inner.set_pairable(pairable).await.map_err(Into::into)
+    }
+
+    /// Sets how long this adapter stays pairable after `set_pairable` is called
+    pub async fn set_pairable_timeout(&self, timeout: Duration) -> Result<()> {
+        self.inner
+            .set_pairable_timeout(timeout_secs(timeout))
+            .await
+            .map_err(Into::into)
     }
 
     /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
@@ -75,20 +178,40 @@ impl AdapterImpl {
 
     /// Attempts to create the device identified by `id`
     pub async fn open_device(&self, id: &DeviceId) -> Result<Device> {
-        Device::new(self.session.clone(), &self.inner, id.0)
+        Device::new(self.session.clone(), &self.inner, id.0).await
     }
 
     /// Finds all connected Bluetooth LE devices
     pub async fn connected_devices(&self) -> Result<Vec<Device>> {
         let mut devices = Vec::new();
-        for device in self
-            .inner
-            .device_addresses()
-            .await?
-            .into_iter()
-            .filter_map(|addr| Device::new(self.session.clone(), &self.inner, addr).ok())
-        {
-            if device.is_connected().await {
+        for addr in self.inner.device_addresses().await? {
+            if let Ok(device) = Device::new(self.session.clone(), &self.inner, addr).await {
+                if device.is_connected().await {
+                    devices.push(device);
+                }
+            }
+        }
+
+        Ok(devices)
+    }
+
+    /// Finds all devices which are paired with the system
+    pub async fn paired_devices(&self) -> Result<Vec<Device>> {
+        let mut devices = Vec::new();
+        for device in self.known_devices().await? {
+            if device.is_paired().await.unwrap_or(false) {
+                devices.push(device);
+            }
+        }
+
+        Ok(devices)
+    }
+
+    /// Finds all devices known to BlueZ
+    pub async fn known_devices(&self) -> Result<Vec<Device>> {
+        let mut devices = Vec::new();
+        for addr in self.inner.device_addresses().await? {
+            if let Ok(device) = Device::new(self.session.clone(), &self.inner, addr).await {
                 devices.push(device);
             }
         }
@@ -130,33 +253,153 @@ impl AdapterImpl {
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
+        self.scan_with_filter(ScanFilter::with_services(services)).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter`.
+    ///
+    /// The services, minimum RSSI and local name prefix of `filter` are applied by BlueZ's discovery filter. All
+    /// criteria are additionally checked in software.
+    pub async fn scan_with_filter(
+        &self,
+        filter: ScanFilter,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        self.scan_with_options(filter, ScanOptions::default()).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter` using the given `options`.
+    ///
+    /// Active scans use BlueZ's device discovery and passive scans use an advertisement monitor.
+    pub async fn scan_with_options(
+        &self,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        let stream: ScanStream<'_> = match options.mode {
+            ScanMode::Active => Box::pin(self.active_scan(filter, options).await?),
+            ScanMode::Passive => Box::pin(self.passive_scan(filter, options).await?),
+        };
+        Ok(stream)
+    }
+
+    /// Scans using BlueZ's device discovery
+    ///
+    /// The options are mapped to BlueZ's discovery filter. BlueZ does not allow both an RSSI and a path loss
+    /// threshold, so if both are set, the path loss is only checked in software.
+    ///
+    /// A device is reported when it is first discovered and again whenever BlueZ signals a change of its properties,
+    /// such as its RSSI, manufacturer data or service data.
+    ///
+    /// BlueZ keeps a single discovery filter per client, so the filter applied is the union of the filters of all
+    /// running scans of this adapter. The filter of this scan is removed from it when the returned stream is dropped.
+    async fn active_scan(
+        &self,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + '_> {
+        // BlueZ matches the pattern against the prefix of the device name (or address)
+        let pattern = filter.local_name.as_ref().map(|x| match x {
+            LocalNameFilter::Exact(name) | LocalNameFilter::Prefix(name) => name.clone(),
+        });
+
+        let guard = DiscoveryFilters::add(
+            &self.discovery_filters,
+            &self.inner,
+            bluer::DiscoveryFilter {
+                uuids: filter.services.iter().copied().collect(),
+                rssi: filter.min_rssi,
+                pathloss: options.max_pathloss.filter(|_| filter.min_rssi.is_none()),
+                transport: match options.transport {
+                    ScanTransport::Le => bluer::DiscoveryTransport::Le,
+                    ScanTransport::Dual => bluer::DiscoveryTransport::Auto,
+                },
+                duplicate_data: options.duplicates != DuplicatePolicy::ReportChanges,
+                pattern,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        // Unlike `discover_devices`, this also reports a `DeviceAdded` event each time the properties of an already
+        // discovered device change
         Ok(self
             .inner
-            .discover_devices()
+            .discover_devices_with_changes()
             .await?
             .then(move |event| {
                 Box::pin(async move {
                     match event {
-                        bluer::AdapterEvent::DeviceAdded(addr) => {
-                            let device = Device::new(self.session.clone(), &self.inner, addr).ok()?;
-                            if !device.is_connected().await {
-                                let adv_data = device.0.adv_data().await;
-                                let rssi = device.rssi().await.ok();
-                                Some(AdvertisingDevice { device, adv_data, rssi })
-                            } else {
-                                None
-                            }
-                        }
+                        bluer::AdapterEvent::DeviceAdded(addr) => self.advertising_device(addr).await,
                         _ => None,
                     }
                 })
             })
             .filter_map(|x| x)
-            .filter(|x: &AdvertisingDevice| {
-                services.is_empty() || x.adv_data.services.iter().any(|y| services.contains(y))
+            .filter(move |x: &AdvertisingDevice| {
+                let _guard = &guard;
+                filter.matches(x) && options.matches(x)
             }))
     }
 
+    /// Scans passively using a BlueZ advertisement monitor
+    ///
+    /// The monitor only reports devices matching its patterns, which are built from `filter`, so scanning without a
+    /// filter on manufacturer data, service data, services or the local name is not supported. A device is reported
+    /// when the monitor finds it and again after it has been lost and found again. The transport and duplicate policy
+    /// of `options` are ignored.
+    async fn passive_scan(
+        &self,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + '_> {
+        let patterns = super::monitor::patterns(&filter);
+        if patterns.is_empty() {
+            return Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "passive scanning requires a filter on manufacturer data, service data, services or the local name"
+                    .to_string(),
+            ));
+        }
+
+        let manager = self.inner.monitor().await?;
+        let monitor = manager
+            .register(Monitor {
+                monitor_type: bluer::monitor::Type::OrPatterns,
+                patterns: Some(patterns),
+                ..Default::default()
+            })
+            .await?;
+
+        Ok(monitor
+            .then(move |event| {
+                Box::pin(async move {
+                    match event {
+                        MonitorEvent::DeviceFound(id) => self.advertising_device(id.device).await,
+                        _ => None,
+                    }
+                })
+            })
+            .filter_map(|x| x)
+            .filter(move |x: &AdvertisingDevice| {
+                // The monitor is unregistered when its manager is dropped
+                let _manager = &manager;
+                filter.matches(x) && options.matches(x)
+            }))
+    }
+
+    /// The advertisement last received from the device with `addr`, unless the device is connected
+    async fn advertising_device(&self, addr: bluer::Address) -> Option<AdvertisingDevice> {
+        let device = Device::new(self.session.clone(), &self.inner, addr).await.ok()?;
+        if !device.is_connected().await {
+            let adv_data = device.0.adv_data().await;
+            let rssi = device.0.inner.rssi().await.ok().flatten();
+            Some(AdvertisingDevice { device, adv_data, rssi })
+        } else {
+            None
+        }
+    }
+
     /// Finds Bluetooth devices providing any service in `services`.
     ///
     /// Returns a stream of [`Device`] structs with matching connected devices returned first. If the stream is not
@@ -167,6 +410,45 @@ impl AdapterImpl {
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = Result<Device>> + Send + Unpin + 'a> {
+        let connected = if services.is_empty() {
+            self.connected_devices().await?
+        } else {
+            self.connected_devices_with_services(services).await?
+        };
+
+        // BlueZ reports every device it already knows about when discovery starts, including the connected devices
+        let mut seen: HashSet<DeviceId> = connected.iter().map(|x| x.id()).collect();
+        let connected = stream::iter(connected).map(Ok);
+
+        // try_unfold is used to ensure we do not start scanning until the connected devices have been consumed
+        let advertising = Box::pin(stream::try_unfold(None, |state| async {
+            let mut stream = match state {
+                Some(stream) => stream,
+                None => self.discover_advertising_devices(services).await?,
+            };
+            Ok(stream.next().await.map(|x| (x, Some(stream))))
+        }))
+        .map(|x: Result<Result<Device>>| x.and_then(|x| x))
+        .filter(move |x| x.as_ref().map_or(true, |device| seen.insert(device.id())));
+
+        Ok(connected.chain(advertising))
+    }
+
+    async fn discover_advertising_devices<'a>(
+        &'a self,
+        services: &'a [Uuid],
+    ) -> Result<impl Stream<Item = Result<Device>> + Send + Unpin + 'a> {
+        // Add to the discovery filters of the running scans instead of replacing them
+        let guard = DiscoveryFilters::add(
+            &self.discovery_filters,
+            &self.inner,
+            bluer::DiscoveryFilter {
+                uuids: services.iter().copied().collect(),
+                ..Default::default()
+            },
+        )
+        .await?;
+
         Ok(self
             .inner
             .discover_devices()
@@ -175,7 +457,7 @@ impl AdapterImpl {
                 Box::pin(async move {
                     match event {
                         bluer::AdapterEvent::DeviceAdded(addr) => {
-                            let device = match Device::new(self.session.clone(), &self.inner, addr) {
+                            let device = match Device::new(self.session.clone(), &self.inner, addr).await {
                                 Ok(device) => device,
                                 Err(err) => return Some(Err(err)),
                             };
@@ -200,7 +482,10 @@ impl AdapterImpl {
                     }
                 })
             })
-            .filter_map(|x| x))
+            .filter_map(move |x| {
+                let _guard = &guard;
+                x
+            }))
     }
 
     /// Connects to the [`Device`]
@@ -222,7 +507,9 @@ impl AdapterImpl {
         let events = device.0.inner.events().await?;
         Ok(events.filter_map(|ev| match ev {
             bluer::DeviceEvent::PropertyChanged(bluer::DeviceProperty::Connected(false)) => {
-                Some(ConnectionEvent::Disconnected)
+                Some(ConnectionEvent::Disconnected {
+                    reason: DisconnectReason::Unknown,
+                })
             }
             bluer::DeviceEvent::PropertyChanged(bluer::DeviceProperty::Connected(true)) => {
                 Some(ConnectionEvent::Connected)
@@ -230,4 +517,116 @@ impl AdapterImpl {
             _ => None,
         }))
     }
+
+    /// Monitors all devices known to the adapter for changes.
+    pub async fn device_events(&self) -> Result<impl Stream<Item = Result<DeviceEvent>> + Send + Unpin + '_> {
+        // Subscribe to adapter events before listing the known devices so that no device added in between is missed
+        let adapter_events: AdapterEventStream = Box::pin(self.inner.events().await?);
+        let mut devices = HashMap::new();
+        for addr in self.inner.device_addresses().await? {
+            // The device may have been removed since it was listed
+            match self.watch_device(addr).await {
+                Ok((device, events)) => {
+                    devices.insert(addr, (device, events));
+                }
+                Err(err) => debug!("Not watching device {}: {:?}", addr, err),
+            }
+        }
+
+        Ok(Box::pin(stream::unfold(
+            (adapter_events, devices),
+            move |(mut adapter_events, mut devices)| async move {
+                loop {
+                    let event = future::poll_fn(|cx| {
+                        if let Poll::Ready(event) = adapter_events.poll_next(cx) {
+                            return Poll::Ready(WatchEvent::Adapter(event));
+                        }
+                        for (addr, (_, events)) in devices.iter_mut() {
+                            if let Poll::Ready(event) = events.poll_next(cx) {
+                                return Poll::Ready(WatchEvent::Device(*addr, event));
+                            }
+                        }
+                        Poll::Pending
+                    })
+                    .await;
+
+                    let event = match event {
+                        WatchEvent::Adapter(None) => return None,
+                        WatchEvent::Adapter(Some(bluer::AdapterEvent::DeviceAdded(addr))) => {
+                            if devices.contains_key(&addr) {
+                                continue;
+                            }
+                            match self.watch_device(addr).await {
+                                Ok((device, events)) => {
+                                    devices.insert(addr, (device.clone(), events));
+                                    Ok(DeviceEvent::Added(device))
+                                }
+                                Err(err) => {
+                                    debug!("Not watching added device {}: {:?}", addr, err);
+                                    continue;
+                                }
+                            }
+                        }
+                        WatchEvent::Adapter(Some(bluer::AdapterEvent::DeviceRemoved(addr))) => {
+                            devices.remove(&addr);
+                            Ok(DeviceEvent::Removed(DeviceId(addr)))
+                        }
+                        WatchEvent::Adapter(Some(_)) => continue,
+                        WatchEvent::Device(addr, None) => {
+                            devices.remove(&addr);
+                            continue;
+                        }
+                        WatchEvent::Device(addr, Some(bluer::DeviceEvent::PropertyChanged(property))) => {
+                            let device = devices[&addr].0.clone();
+                            match property {
+                                bluer::DeviceProperty::Connected(true) => Ok(DeviceEvent::Connected(device)),
+                                bluer::DeviceProperty::Connected(false) => Ok(DeviceEvent::Disconnected {
+                                    device,
+                                    reason: DisconnectReason::Unknown,
+                                }),
+                                bluer::DeviceProperty::Paired(true) => Ok(DeviceEvent::Paired(device)),
+                                bluer::DeviceProperty::Paired(false) => Ok(DeviceEvent::Unpaired(device)),
+                                bluer::DeviceProperty::Alias(name) => {
+                                    device.0.set_name(name.clone());
+                                    Ok(DeviceEvent::NameChanged { device, name })
+                                }
+                                _ => continue,
+                            }
+                        }
+                    };
+                    return Some((event, (adapter_events, devices)));
+                }
+            },
+        )))
+    }
+
+    /// Listen for L2CAP connection-oriented channels opened by remote devices
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(
+        &self,
+        psm: Option<u16>,
+        security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::Listener> {
+        super::l2cap_channel::Listener::bind(self.session.clone(), self.inner.clone(), psm, security).await
+    }
+
+    async fn watch_device(&self, addr: bluer::Address) -> Result<(Device, DeviceEventStream)> {
+        let device = Device::new(self.session.clone(), &self.inner, addr).await?;
+        let events = device.0.inner.events().await?;
+        Ok((device, Box::pin(events)))
+    }
+}
+
+/// Converts a timeout to whole seconds as expected by BlueZ
+fn timeout_secs(timeout: Duration) -> u32 {
+    timeout.as_secs().try_into().unwrap_or(u32::MAX)
+}
+
+type ScanStream<'a> = Pin<Box<dyn Stream<Item = AdvertisingDevice> + Send + 'a>>;
+type AdapterEventStream = Pin<Box<dyn Stream<Item = bluer::AdapterEvent> + Send>>;
+type DeviceEventStream = Pin<Box<dyn Stream<Item = bluer::DeviceEvent> + Send>>;
+
+enum WatchEvent {
+    Adapter(Option<bluer::AdapterEvent>),
+    Device(bluer::Address, Option<bluer::DeviceEvent>),
 }
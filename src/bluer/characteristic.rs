@@ -1,3 +1,6 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
 use bluer::gatt::remote::CharacteristicWriteRequest;
 use bluer::gatt::WriteOp;
 use futures_core::Stream;
@@ -5,10 +8,15 @@ use futures_lite::StreamExt;
 
 use crate::{Characteristic, CharacteristicProperties, Descriptor, Result, Uuid};
 
+/// The minimum ATT MTU for Bluetooth LE
+const DEFAULT_ATT_MTU: usize = 23;
+
 /// A Bluetooth GATT characteristic
 #[derive(Debug, Clone)]
 pub struct CharacteristicImpl {
     inner: bluer::gatt::remote::Characteristic,
+    uuid: Uuid,
+    mtu: Arc<AtomicUsize>,
 }
 
 impl PartialEq for CharacteristicImpl {
@@ -32,33 +40,28 @@ impl std::hash::Hash for CharacteristicImpl {
 }
 
 impl Characteristic {
-    pub(super) fn new(inner: bluer::gatt::remote::Characteristic) -> Characteristic {
-        Characteristic(CharacteristicImpl { inner })
+    pub(super) async fn new(inner: bluer::gatt::remote::Characteristic) -> Result<Characteristic> {
+        // The UUID never changes, so read it once to allow synchronous access. The MTU is refreshed by
+        // `max_write_len_async`. Older versions of BlueZ do not report it, so fall back to the minimum ATT MTU.
+        let uuid = inner.uuid().await?;
+        let mtu = inner.mtu().await.unwrap_or(DEFAULT_ATT_MTU);
+        Ok(Characteristic(CharacteristicImpl {
+            inner,
+            uuid,
+            mtu: Arc::new(AtomicUsize::new(mtu)),
+        }))
     }
 }
 
 impl CharacteristicImpl {
     /// The [`Uuid`] identifying the type of this GATT characteristic
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if there is a current Tokio runtime and it is single-threaded, if there is no current
-    /// Tokio runtime and creating one fails, or if the underlying [`CharacteristicImpl::uuid_async()`] method fails.
     pub fn uuid(&self) -> Uuid {
-        // Call an async function from a synchronous context
-        match tokio::runtime::Handle::try_current() {
-            Ok(handle) => tokio::task::block_in_place(move || handle.block_on(self.uuid_async())),
-            Err(_) => tokio::runtime::Builder::new_current_thread()
-                .build()
-                .unwrap()
-                .block_on(self.uuid_async()),
-        }
-        .unwrap()
+        self.uuid
     }
 
     /// The [`Uuid`] identifying the type of this GATT characteristic
     pub async fn uuid_async(&self) -> Result<Uuid> {
-        self.inner.uuid().await.map_err(Into::into)
+        Ok(self.uuid)
     }
 
     /// The properties of this this GATT characteristic.
@@ -102,20 +105,18 @@ impl CharacteristicImpl {
     }
 
     /// Get the maximum amount of data that can be written in a single packet for this characteristic.
+    ///
+    /// Returns the value based on the MTU when the characteristic was discovered or last queried with
+    /// [`max_write_len_async`][Self::max_write_len_async].
     pub fn max_write_len(&self) -> Result<usize> {
-        // Call an async function from a synchronous context
-        match tokio::runtime::Handle::try_current() {
-            Ok(handle) => tokio::task::block_in_place(move || handle.block_on(self.max_write_len_async())),
-            Err(_) => tokio::runtime::Builder::new_current_thread()
-                .build()
-                .unwrap()
-                .block_on(self.max_write_len_async()),
-        }
+        // GATT characteristic writes have 3 bytes of overhead (opcode + handle id)
+        Ok(self.mtu.load(Ordering::Relaxed) - 3)
     }
 
     /// Get the maximum amount of data that can be written in a single packet for this characteristic.
     pub async fn max_write_len_async(&self) -> Result<usize> {
         let mtu = self.inner.mtu().await?;
+        self.mtu.store(mtu, Ordering::Relaxed);
         // GATT characteristic writes have 3 bytes of overhead (opcode + handle id)
         Ok(mtu - 3)
     }
@@ -124,6 +125,11 @@ impl CharacteristicImpl {
     ///
     /// Returns a stream of values for the characteristic sent from the device.
     pub async fn notify(&self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + '_> {
+        self.clone().notify_owned().await
+    }
+
+    /// Like [`notify`][Self::notify], but the returned stream owns the characteristic.
+    pub async fn notify_owned(self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + 'static> {
         Ok(Box::pin(self.inner.notify().await?.map(Ok)))
     }
 
@@ -141,11 +147,12 @@ impl CharacteristicImpl {
     ///
     /// If no descriptors have been discovered yet, this method will perform descriptor discovery.
     pub async fn descriptors(&self) -> Result<Vec<Descriptor>> {
-        self.inner
-            .descriptors()
-            .await
-            .map_err(Into::into)
-            .map(|x| x.into_iter().map(Descriptor::new).collect())
+        let descriptors = self.inner.descriptors().await?;
+        let mut res = Vec::with_capacity(descriptors.len());
+        for descriptor in descriptors {
+            res.push(Descriptor::new(descriptor).await?);
+        }
+        Ok(res)
     }
 }
 
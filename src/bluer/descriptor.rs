@@ -4,6 +4,7 @@ use crate::{Descriptor, Result, Uuid};
 #[derive(Debug, Clone)]
 pub struct DescriptorImpl {
     inner: bluer::gatt::remote::Descriptor,
+    uuid: Uuid,
 }
 
 impl PartialEq for DescriptorImpl {
@@ -19,33 +20,22 @@ impl PartialEq for DescriptorImpl {
 impl Eq for DescriptorImpl {}
 
 impl Descriptor {
-    pub(super) fn new(inner: bluer::gatt::remote::Descriptor) -> Descriptor {
-        Descriptor(DescriptorImpl { inner })
+    pub(super) async fn new(inner: bluer::gatt::remote::Descriptor) -> Result<Descriptor> {
+        // The UUID never changes, so read it once to allow synchronous access
+        let uuid = inner.uuid().await?;
+        Ok(Descriptor(DescriptorImpl { inner, uuid }))
     }
 }
 
 impl DescriptorImpl {
     /// The [`Uuid`] identifying the type of this GATT descriptor
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if there is a current Tokio runtime and it is single-threaded, if there is no current
-    /// Tokio runtime and creating one fails, or if the underlying [`DescriptorImpl::uuid_async()`] method fails.
     pub fn uuid(&self) -> Uuid {
-        // Call an async function from a synchronous context
-        match tokio::runtime::Handle::try_current() {
-            Ok(handle) => tokio::task::block_in_place(move || handle.block_on(self.uuid_async())),
-            Err(_) => tokio::runtime::Builder::new_current_thread()
-                .build()
-                .unwrap()
-                .block_on(self.uuid_async()),
-        }
-        .unwrap()
+        self.uuid
     }
 
     /// The [`Uuid`] identifying the type of this GATT descriptor
     pub async fn uuid_async(&self) -> Result<Uuid> {
-        self.inner.uuid().await.map_err(Into::into)
+        Ok(self.uuid)
     }
 
     /// The cached value of this descriptor
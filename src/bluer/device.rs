@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use futures_core::Stream;
 use futures_lite::StreamExt;
@@ -6,14 +6,17 @@ use futures_lite::StreamExt;
 use super::DeviceId;
 use crate::device::ServicesChanged;
 use crate::error::ErrorKind;
-use crate::pairing::PairingAgent;
-use crate::{btuuid, AdvertisementData, Device, Error, ManufacturerData, Result, Service, Uuid};
+use crate::pairing::{PairingAgent, PairingMethod, PairingResult};
+use crate::{
+    btuuid, AdvertisementData, Device, DeviceProperty, Error, ManufacturerData, Result, SecurityLevel, Service, Uuid,
+};
 
 /// A Bluetooth LE device
 #[derive(Debug, Clone)]
 pub struct DeviceImpl {
     pub(super) inner: Arc<bluer::Device>,
     session: Arc<bluer::Session>,
+    name: Arc<Mutex<Option<String>>>,
 }
 
 impl PartialEq for DeviceImpl {
@@ -38,15 +41,26 @@ impl std::fmt::Display for DeviceImpl {
 }
 
 impl Device {
-    pub(super) fn new(session: Arc<bluer::Session>, adapter: &bluer::Adapter, addr: bluer::Address) -> Result<Device> {
-        Ok(Device(DeviceImpl {
-            inner: Arc::new(adapter.device(addr)?),
-            session,
-        }))
+    pub(super) async fn new(
+        session: Arc<bluer::Session>,
+        adapter: &bluer::Adapter,
+        addr: bluer::Address,
+    ) -> Result<Device> {
+        Ok(Device(DeviceImpl::new(session, adapter.device(addr)?).await))
     }
 }
 
 impl DeviceImpl {
+    async fn new(session: Arc<bluer::Session>, device: bluer::Device) -> Self {
+        // Read the name up front so that `name` can return it without blocking
+        let name = device.alias().await.ok();
+        DeviceImpl {
+            inner: Arc::new(device),
+            session,
+            name: Arc::new(Mutex::new(name)),
+        }
+    }
+
     /// This device's unique identifier
     pub fn id(&self) -> DeviceId {
         DeviceId(self.inner.address())
@@ -56,28 +70,38 @@ impl DeviceImpl {
     ///
     /// This can either be a name advertised or read from the device, or a name assigned to the device by the OS.
     ///
-    /// # Panics
-    ///
-    /// This method will panic if there is a current Tokio runtime and it is single-threaded, if there is no current
-    /// Tokio runtime and creating one fails, or if the underlying [`DeviceImpl::name_async()`] method fails.
+    /// Returns the name when this device was created or last queried with [`name_async`][Self::name_async], which
+    /// should be used to get the current name.
     pub fn name(&self) -> Result<String> {
-        // Call an async function from a synchronous context
-        match tokio::runtime::Handle::try_current() {
-            Ok(handle) => tokio::task::block_in_place(move || handle.block_on(self.name_async())),
-            Err(_) => tokio::runtime::Builder::new_current_thread()
-                .build()
-                .unwrap()
-                .block_on(self.name_async()),
-        }
+        self.name
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, "device name is not available".to_string()))
     }
 
     /// The local name for this device, if available
     ///
     /// This can either be a name advertised or read from the device, or a name assigned to the device by the OS.
+    ///
+    /// This is the alias BlueZ reports for the device, which may have been set by the user and falls back to the
+    /// device's address if no name is known. The name actually advertised by the device is reported in
+    /// [`AdvertisementData::local_name`].
     pub async fn name_async(&self) -> Result<String> {
+        let name = self.inner.alias().await?;
+        *self.name.lock().unwrap() = Some(name.clone());
+        Ok(name)
+    }
+
+    /// The name assigned to this device by BlueZ
+    pub async fn alias(&self) -> Result<String> {
         self.inner.alias().await.map_err(Into::into)
     }
 
+    pub(super) fn set_name(&self, name: String) {
+        *self.name.lock().unwrap() = Some(name);
+    }
+
     /// The connection status for this device
     pub async fn is_connected(&self) -> bool {
         self.inner.is_connected().await.unwrap_or(false)
@@ -89,20 +113,30 @@ impl DeviceImpl {
     }
 
     /// Attempt to pair this device using the system default pairing UI
-    pub async fn pair(&self) -> Result<()> {
-        if self.is_paired().await? {
-            return Ok(());
+    ///
+    /// BlueZ does not report the association model or the resulting security level.
+    pub async fn pair(&self) -> Result<PairingResult> {
+        if !self.is_paired().await? {
+            self.inner.pair().await?;
         }
 
-        self.inner.pair().await.map_err(Into::into)
+        Ok(PairingResult {
+            bonded: self.inner.is_bonded().await?,
+            method: None,
+            security_level: None,
+        })
     }
 
     /// Attempt to pair this device using the system default pairing UI
-    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, agent: &T) -> Result<()> {
+    ///
+    /// The association model is inferred from the agent callbacks invoked by BlueZ.
+    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, agent: &T) -> Result<PairingResult> {
         if self.is_paired().await? {
-            return Ok(());
+            return self.pair().await;
         }
 
+        let method = Arc::new(Mutex::new(None));
+
         let agent = {
             // Safety: This `bluer::agent::Agent`, including the encapsulated closures and async blocks will be dropped
             // when the `_handle` below is dropped. Therefore, the lifetime of the captures of `agent` will not
@@ -117,17 +151,44 @@ impl DeviceImpl {
             ) -> Result<Device, bluer::agent::ReqError> {
                 let adapter = session.adapter(adapter).map_err(|_| bluer::agent::ReqError::Rejected)?;
                 let device = adapter.device(addr).map_err(|_| bluer::agent::ReqError::Rejected)?;
-                Ok(Device(DeviceImpl {
-                    inner: Arc::new(device),
-                    session,
-                }))
+                Ok(Device(DeviceImpl::new(session, device).await))
             }
 
             bluer::agent::Agent {
+                request_pin_code: Some(Box::new({
+                    let session = self.session.clone();
+                    let method = method.clone();
+                    move |req: bluer::agent::RequestPinCode| {
+                        let session = session.clone();
+                        *method.lock().unwrap() = Some(PairingMethod::LegacyPin);
+                        Box::pin(async move {
+                            let device = req_device(session, &req.adapter, req.device).await?;
+                            agent
+                                .request_pin_code(&device)
+                                .await
+                                .map_err(|_| bluer::agent::ReqError::Rejected)
+                        })
+                    }
+                })),
+                display_pin_code: Some(Box::new({
+                    let session = self.session.clone();
+                    let method = method.clone();
+                    move |req: bluer::agent::DisplayPinCode| {
+                        let session = session.clone();
+                        *method.lock().unwrap() = Some(PairingMethod::LegacyPin);
+                        Box::pin(async move {
+                            let device = req_device(session, &req.adapter, req.device).await?;
+                            agent.display_pin_code(&device, &req.pincode);
+                            Ok(())
+                        })
+                    }
+                })),
                 request_passkey: Some(Box::new({
                     let session = self.session.clone();
+                    let method = method.clone();
                     move |req: bluer::agent::RequestPasskey| {
                         let session = session.clone();
+                        *method.lock().unwrap() = Some(PairingMethod::PasskeyEntry);
                         Box::pin(async move {
                             let device = req_device(session, &req.adapter, req.device).await?;
                             match agent.request_passkey(&device).await {
@@ -139,8 +200,10 @@ impl DeviceImpl {
                 })),
                 display_passkey: Some(Box::new({
                     let session = self.session.clone();
+                    let method = method.clone();
                     move |req: bluer::agent::DisplayPasskey| {
                         let session = session.clone();
+                        *method.lock().unwrap() = Some(PairingMethod::PasskeyEntry);
                         Box::pin(async move {
                             let device = req_device(session, &req.adapter, req.device).await?;
                             if let Ok(passkey) = req.passkey.try_into() {
@@ -154,8 +217,10 @@ impl DeviceImpl {
                 })),
                 request_confirmation: Some(Box::new({
                     let session = self.session.clone();
+                    let method = method.clone();
                     move |req: bluer::agent::RequestConfirmation| {
                         let session = session.clone();
+                        *method.lock().unwrap() = Some(PairingMethod::NumericComparison);
                         Box::pin(async move {
                             let session = session.clone();
                             let device = req_device(session, &req.adapter, req.device).await?;
@@ -170,13 +235,44 @@ impl DeviceImpl {
                         })
                     }
                 })),
+                request_authorization: Some(Box::new({
+                    let session = self.session.clone();
+                    let method = method.clone();
+                    move |req: bluer::agent::RequestAuthorization| {
+                        let session = session.clone();
+                        *method.lock().unwrap() = Some(PairingMethod::JustWorks);
+                        Box::pin(async move {
+                            let device = req_device(session, &req.adapter, req.device).await?;
+                            agent
+                                .authorize(&device)
+                                .await
+                                .map_err(|_| bluer::agent::ReqError::Rejected)
+                        })
+                    }
+                })),
+                authorize_service: Some(Box::new({
+                    let session = self.session.clone();
+                    move |req: bluer::agent::AuthorizeService| {
+                        let session = session.clone();
+                        Box::pin(async move {
+                            let device = req_device(session, &req.adapter, req.device).await?;
+                            agent
+                                .authorize_service(&device, req.service)
+                                .await
+                                .map_err(|_| bluer::agent::ReqError::Rejected)
+                        })
+                    }
+                })),
                 ..Default::default()
             }
         };
 
         let _handle = self.session.register_agent(agent).await?;
 
-        self.pair().await
+        let mut res = self.pair().await?;
+        // BlueZ accepts locally initiated "JustWorks" pairing without consulting the agent
+        res.method = method.lock().unwrap().or(Some(PairingMethod::JustWorks));
+        Ok(res)
     }
 
     /// Disconnect and unpair this device from the system
@@ -189,6 +285,20 @@ impl DeviceImpl {
         adapter.remove_device(self.inner.address()).await.map_err(Into::into)
     }
 
+    /// The security level of the current connection to this device
+    ///
+    /// Not supported on Linux.
+    pub async fn security_level(&self) -> Result<SecurityLevel> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether the current connection to this device is encrypted
+    ///
+    /// Not supported on Linux.
+    pub async fn is_encrypted(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Discover the primary services of this device.
     pub async fn discover_services(&self) -> Result<Vec<Service>> {
         self.services().await
@@ -208,13 +318,12 @@ impl DeviceImpl {
     ///
     /// If no services have been discovered yet, this method will perform service discovery.
     pub async fn services(&self) -> Result<Vec<Service>> {
-        Ok(self
-            .inner
-            .services()
-            .await?
-            .into_iter()
-            .map(|x| Service::new(self.inner.clone(), x))
-            .collect())
+        let services = self.inner.services().await?;
+        let mut res = Vec::with_capacity(services.len());
+        for service in services {
+            res.push(Service::new(self.inner.clone(), service).await?);
+        }
+        Ok(res)
     }
 
     /// Monitors the device for services changed events.
@@ -247,29 +356,100 @@ impl DeviceImpl {
         }))
     }
 
+    /// Monitors the device for changes of its properties.
+    pub async fn property_changes(&self) -> Result<impl Stream<Item = DeviceProperty> + Send + Unpin + '_> {
+        use bluer::DeviceEvent::PropertyChanged;
+
+        let events = self.inner.events().await?;
+        Ok(events.filter_map(move |event| match event {
+            PropertyChanged(bluer::DeviceProperty::Alias(name)) => {
+                self.set_name(name.clone());
+                Some(DeviceProperty::Name(name))
+            }
+            PropertyChanged(bluer::DeviceProperty::Rssi(rssi)) => Some(DeviceProperty::Rssi(rssi)),
+            PropertyChanged(bluer::DeviceProperty::TxPower(tx_power)) => Some(DeviceProperty::TxPower(tx_power)),
+            PropertyChanged(bluer::DeviceProperty::Paired(paired)) => Some(DeviceProperty::Paired(paired)),
+            PropertyChanged(bluer::DeviceProperty::Trusted(trusted)) => Some(DeviceProperty::Trusted(trusted)),
+            PropertyChanged(bluer::DeviceProperty::ServicesResolved(resolved)) => {
+                Some(DeviceProperty::ServicesResolved(resolved))
+            }
+            PropertyChanged(bluer::DeviceProperty::ManufacturerData(data)) => {
+                let mut data: Vec<ManufacturerData> = data
+                    .into_iter()
+                    .map(|(company_id, data)| ManufacturerData { company_id, data })
+                    .collect();
+                data.sort();
+                Some(DeviceProperty::ManufacturerData(data))
+            }
+            PropertyChanged(bluer::DeviceProperty::ServiceData(data)) => Some(DeviceProperty::ServiceData(data)),
+            _ => None,
+        }))
+    }
+
     /// Get the current signal strength from the device in dBm.
     ///
     /// # Platform specific
     ///
-    /// Returns [ErrorKind::NotSupported].
+    /// BlueZ only knows the signal strength of advertisements, so this returns the RSSI of the most recent
+    /// advertisement received from the device, if any.
     pub async fn rssi(&self) -> Result<i16> {
-        Err(ErrorKind::NotSupported.into())
+        if let Some(rssi) = self.inner.rssi().await? {
+            Ok(rssi)
+        } else if self.inner.is_connected().await? {
+            Err(connected_rssi_not_supported())
+        } else {
+            Err(Error::new(
+                ErrorKind::NotFound,
+                None,
+                "no advertisement has been received from the device".to_string(),
+            ))
+        }
+    }
+
+    /// Waits for the next change of the signal strength of the device.
+    ///
+    /// Discovery is started until the device advertises again. Connected devices do not advertise, so this fails
+    /// immediately for them.
+    pub async fn refresh_rssi(&self) -> Result<i16> {
+        if self.inner.is_connected().await? {
+            return Err(connected_rssi_not_supported());
+        }
+
+        let mut events = self.inner.events().await?;
+        let adapter = self.session.adapter(self.inner.adapter_name())?;
+        // Discovery continues as long as the stream is alive
+        let _discovery = adapter.discover_devices_with_changes().await?;
+
+        while let Some(event) = events.next().await {
+            if let bluer::DeviceEvent::PropertyChanged(bluer::DeviceProperty::Rssi(rssi)) = event {
+                return Ok(rssi);
+            }
+        }
+
+        Err(Error::new(
+            ErrorKind::NotFound,
+            None,
+            "device was removed while waiting for its RSSI".to_string(),
+        ))
     }
 
     pub(super) async fn adv_data(&self) -> AdvertisementData {
         let device = &self.inner;
 
-        let is_connectable = true;
-
-        let local_name = device.alias().await.unwrap_or_default();
-        let local_name = (!local_name.is_empty()).then_some(local_name);
+        // Unlike the alias returned by `name`, this is the name actually advertised by (or read from) the device
+        let local_name = device.name().await.unwrap_or_default();
 
-        let manufacturer_data = device
+        let mut all_manufacturer_data: Vec<ManufacturerData> = device
             .manufacturer_data()
             .await
             .unwrap_or_default()
-            .and_then(|data| data.into_iter().next())
-            .map(|(company_id, data)| ManufacturerData { company_id, data });
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(company_id, data)| ManufacturerData { company_id, data })
+            .collect();
+        // BlueZ reports the entries as a map, so order them by company id to keep the result stable
+        all_manufacturer_data.sort();
+        let manufacturer_data = all_manufacturer_data.first().cloned();
 
         let tx_power_level = device.tx_power().await.unwrap_or_default();
 
@@ -281,18 +461,47 @@ impl DeviceImpl {
             .unwrap_or_default()
             .map_or(Vec::new(), |x| x.into_iter().collect());
 
+        let flags = device.advertising_flags().await.unwrap_or_default().first().copied();
+
+        let appearance = device.appearance().await.unwrap_or_default();
+
         AdvertisementData {
             local_name,
             manufacturer_data,
+            all_manufacturer_data,
             service_data,
             services,
             tx_power_level,
-            is_connectable,
+            flags,
+            appearance,
+            // BlueZ does not report the advertising PDU type, so whether the device is connectable is unknown
+            is_connectable: true,
+            ..Default::default()
         }
     }
 
     #[cfg(feature = "l2cap")]
-    pub async fn open_l2cap_channel(&self, psm: u16, secure: bool) -> Result<super::l2cap_channel::Channel> {
+    pub async fn open_l2cap_channel(
+        &self,
+        psm: u16,
+        security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::Channel> {
+        let sa = self.l2cap_socket_addr(psm).await?;
+        super::l2cap_channel::Channel::new(sa, security).await
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_packet_channel(
+        &self,
+        psm: u16,
+        security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::PacketChannel> {
+        let sa = self.l2cap_socket_addr(psm).await?;
+        super::l2cap_channel::PacketChannel::new(sa, security).await
+    }
+
+    #[cfg(feature = "l2cap")]
+    async fn l2cap_socket_addr(&self, psm: u16) -> Result<bluer::l2cap::SocketAddr> {
         let address_type = self.inner.address_type().await.map_err(|err| {
             crate::Error::new(
                 crate::error::ErrorKind::Internal,
@@ -300,11 +509,19 @@ impl DeviceImpl {
                 "Could not get address".to_owned(),
             )
         })?;
-        let sa = bluer::l2cap::SocketAddr::new(self.inner.address(), address_type, psm);
-        super::l2cap_channel::Channel::new(sa, secure).await
+        Ok(bluer::l2cap::SocketAddr::new(self.inner.address(), address_type, psm))
     }
 }
 
+/// BlueZ has no API for reading the RSSI of a connection
+fn connected_rssi_not_supported() -> Error {
+    Error::new(
+        ErrorKind::NotSupported,
+        None,
+        "the signal strength of connected devices is not available".to_string(),
+    )
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ServicesChangedImpl(std::ops::RangeInclusive<u16>);
 
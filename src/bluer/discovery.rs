@@ -0,0 +1,122 @@
+//! Sharing of the BlueZ discovery filter between concurrent scans
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use bluer::{DiscoveryFilter, DiscoveryTransport};
+use tracing::debug;
+
+use crate::Result;
+
+/// The discovery filters of the running scans of an adapter
+///
+/// BlueZ keeps a single discovery filter per client and adapter, so the filter applied is the union of the filters of
+/// all running scans. Each scan checks its own criteria in software, so it is not affected by the others.
+#[derive(Debug, Default)]
+pub struct DiscoveryFilters {
+    next_id: u64,
+    /// Incremented on every change, to detect changes made while the filter is being applied
+    generation: u64,
+    filters: HashMap<u64, DiscoveryFilter>,
+}
+
+impl DiscoveryFilters {
+    /// Adds the filter of a scan starting on `adapter` and applies the union of the filters of all running scans
+    ///
+    /// The filter is removed again when the returned guard is dropped.
+    pub async fn add(
+        this: &Arc<Mutex<Self>>,
+        adapter: &bluer::Adapter,
+        filter: DiscoveryFilter,
+    ) -> Result<DiscoveryFilterGuard> {
+        let id = {
+            let mut filters = this.lock().unwrap();
+            let id = filters.next_id;
+            filters.next_id += 1;
+            filters.generation += 1;
+            filters.filters.insert(id, filter);
+            id
+        };
+
+        let guard = DiscoveryFilterGuard {
+            filters: this.clone(),
+            adapter: adapter.clone(),
+            id,
+        };
+        apply(this, adapter).await?;
+        Ok(guard)
+    }
+}
+
+/// Removes the filter of a scan from [`DiscoveryFilters`] when dropped
+#[derive(Debug)]
+pub struct DiscoveryFilterGuard {
+    filters: Arc<Mutex<DiscoveryFilters>>,
+    adapter: bluer::Adapter,
+    id: u64,
+}
+
+impl Drop for DiscoveryFilterGuard {
+    fn drop(&mut self) {
+        {
+            let mut filters = self.filters.lock().unwrap();
+            filters.filters.remove(&self.id);
+            filters.generation += 1;
+        }
+
+        // Applying the filter is asynchronous, so it can only be done from within a tokio runtime
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let filters = self.filters.clone();
+            let adapter = self.adapter.clone();
+            handle.spawn(async move {
+                if let Err(err) = apply(&filters, &adapter).await {
+                    debug!("Error applying discovery filter: {:?}", err);
+                }
+            });
+        }
+    }
+}
+
+/// Applies the union of the filters of all running scans, or the default filter if there are none
+async fn apply(filters: &Mutex<DiscoveryFilters>, adapter: &bluer::Adapter) -> bluer::Result<()> {
+    loop {
+        let (generation, filter) = {
+            let filters = filters.lock().unwrap();
+            (filters.generation, merge(filters.filters.values()))
+        };
+        adapter.set_discovery_filter(filter).await?;
+
+        // A scan may have started or stopped while the filter was being applied
+        if filters.lock().unwrap().generation == generation {
+            return Ok(());
+        }
+    }
+}
+
+/// Merges `filters` into a filter which lets through every device any of them lets through
+fn merge<'a>(filters: impl IntoIterator<Item = &'a DiscoveryFilter>) -> DiscoveryFilter {
+    let mut filters = filters.into_iter();
+    let Some(first) = filters.next() else {
+        return DiscoveryFilter::default();
+    };
+
+    filters.fold(first.clone(), |mut merged, filter| {
+        // An empty list of UUIDs matches every device
+        if merged.uuids.is_empty() || filter.uuids.is_empty() {
+            merged.uuids.clear();
+        } else {
+            merged.uuids.extend(filter.uuids.iter().copied());
+        }
+        merged.rssi = merged.rssi.zip(filter.rssi).map(|(a, b)| a.min(b));
+        merged.pathloss = merged.pathloss.zip(filter.pathloss).map(|(a, b)| a.max(b));
+        if merged.transport != filter.transport {
+            merged.transport = DiscoveryTransport::Auto;
+        }
+        merged.duplicate_data |= filter.duplicate_data;
+        merged.discoverable &= filter.discoverable;
+        if merged.pattern != filter.pattern {
+            merged.pattern = None;
+        }
+        merged
+    })
+}
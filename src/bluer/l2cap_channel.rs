@@ -1,17 +1,16 @@
 use std::io::Result;
 use std::pin::{pin, Pin};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use bluer::l2cap::{SocketAddr, Stream};
+pub use bluer::l2cap::stream::{OwnedReadHalf as Reader, OwnedWriteHalf as Writer};
+use bluer::l2cap::{SeqPacket, Socket, SocketAddr, Stream, StreamListener};
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tracing::trace;
 
+use super::DeviceId;
 use crate::error::ErrorKind;
-use crate::L2CapChannelImpl;
-
-pub use bluer::l2cap::stream::{OwnedReadHalf as Reader, OwnedWriteHalf as Writer};
-
-const SECURE_CHANNEL_KEY_SIZE: u8 = 16;
+use crate::{Device, L2CapChannelImpl, L2CapFlowControl, L2CapSecurity, SecurityLevel};
 
 #[derive(Debug)]
 pub struct Channel {
@@ -21,24 +20,47 @@ pub struct Channel {
 enum ChannelCreationError {
     SetSecurityError(std::io::Error),
     ConnectionError(std::io::Error),
+    BindError(std::io::Error),
+    AcceptError(std::io::Error),
+}
+
+/// Sets the security required of the link
+///
+/// This must be called before the socket is connected or starts listening, otherwise the link is set up with the
+/// default security level.
+fn set_security<T>(socket: &Socket<T>, security: L2CapSecurity) -> std::io::Result<()> {
+    if security.level > SecurityLevel::Low {
+        let level = match security.level {
+            SecurityLevel::Low => bluer::l2cap::SecurityLevel::Low,
+            SecurityLevel::Medium => bluer::l2cap::SecurityLevel::Medium,
+            SecurityLevel::High => bluer::l2cap::SecurityLevel::High,
+            SecurityLevel::Fips => bluer::l2cap::SecurityLevel::Fips,
+        };
+        socket.set_security(bluer::l2cap::Security {
+            level,
+            key_size: security.key_size,
+        })?;
+    }
+    Ok(())
 }
 
 impl Channel {
-    pub async fn new(sa: SocketAddr, secure: bool) -> crate::Result<Self> {
-        let stream = Stream::connect(sa)
+    pub async fn new(sa: SocketAddr, security: L2CapSecurity) -> crate::Result<Self> {
+        let socket = Socket::<Stream>::new_stream().map_err(ChannelCreationError::ConnectionError)?;
+        socket
+            .bind(SocketAddr::any_le())
+            .map_err(ChannelCreationError::ConnectionError)?;
+        set_security(&socket, security).map_err(ChannelCreationError::SetSecurityError)?;
+
+        let stream = socket
+            .connect(sa)
             .await
             .map_err(ChannelCreationError::ConnectionError)?;
 
-        if secure {
-            stream
-                .as_ref()
-                .set_security(bluer::l2cap::Security {
-                    level: bluer::l2cap::SecurityLevel::High,
-                    key_size: SECURE_CHANNEL_KEY_SIZE,
-                })
-                .map_err(ChannelCreationError::SetSecurityError)?;
-        }
+        Ok(Self::from_stream(stream))
+    }
 
+    fn from_stream(stream: Stream) -> Self {
         trace!(name: "Bluetooth Stream",
             "Local address: {:?}\n Remote address: {:?}\n Send MTU: {:?}\n Recv MTU: {:?}\n Security: {:?}\n Flow control: {:?}",
             stream.as_ref().local_addr(),
@@ -49,13 +71,174 @@ impl Channel {
             stream.as_ref().flow_control(),
         );
 
-        Ok(Self { stream })
+        Self { stream }
+    }
+}
+
+#[derive(Debug)]
+pub struct PacketChannel {
+    socket: SeqPacket,
+}
+
+impl PacketChannel {
+    pub async fn new(sa: SocketAddr, security: L2CapSecurity) -> crate::Result<Self> {
+        let socket = Socket::<SeqPacket>::new_seq_packet().map_err(ChannelCreationError::ConnectionError)?;
+        socket
+            .bind(SocketAddr::any_le())
+            .map_err(ChannelCreationError::ConnectionError)?;
+        set_security(&socket, security).map_err(ChannelCreationError::SetSecurityError)?;
+
+        let socket = socket
+            .connect(sa)
+            .await
+            .map_err(ChannelCreationError::ConnectionError)?;
+
+        Ok(Self { socket })
+    }
+
+    pub async fn send(&self, packet: &[u8]) -> crate::Result<()> {
+        let mtu = self.send_mtu()?;
+        if packet.len() > mtu {
+            return Err(crate::Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                format!("packet of {} bytes exceeds the send MTU of {mtu} bytes", packet.len()),
+            ));
+        }
+
+        self.socket.send(packet).await.map_err(io_error)?;
+        Ok(())
+    }
+
+    pub async fn recv(&self) -> crate::Result<Vec<u8>> {
+        let mut buf = vec![0; self.recv_mtu()?];
+        let len = self.socket.recv(&mut buf).await.map_err(io_error)?;
+        if len == 0 {
+            return Err(crate::Error::new(
+                ErrorKind::NotConnected,
+                None,
+                "channel closed".to_owned(),
+            ));
+        }
+
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    pub fn send_mtu(&self) -> crate::Result<usize> {
+        self.socket.as_ref().send_mtu().map(usize::from).map_err(io_error)
+    }
+
+    pub fn recv_mtu(&self) -> crate::Result<usize> {
+        self.socket.as_ref().recv_mtu().map(usize::from).map_err(io_error)
+    }
+}
+
+fn io_error(err: std::io::Error) -> crate::Error {
+    crate::Error::new(ErrorKind::Other, Some(Box::new(err)), String::new())
+}
+
+pub struct Listener {
+    listener: StreamListener,
+    psm: u16,
+    adapter: bluer::Adapter,
+    session: Arc<bluer::Session>,
+}
+
+impl std::fmt::Debug for Listener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Listener")
+            .field("adapter", &self.adapter.name())
+            .field("psm", &self.psm)
+            .finish()
+    }
+}
+
+impl Listener {
+    pub async fn bind(
+        session: Arc<bluer::Session>,
+        adapter: bluer::Adapter,
+        psm: Option<u16>,
+        security: L2CapSecurity,
+    ) -> crate::Result<Self> {
+        // PSM 0 requests a dynamically assigned PSM
+        let sa = SocketAddr::new(adapter.address().await?, bluer::AddressType::LePublic, psm.unwrap_or(0));
+        let socket = Socket::<Stream>::new_stream().map_err(ChannelCreationError::BindError)?;
+        socket.bind(sa).map_err(ChannelCreationError::BindError)?;
+        set_security(&socket, security).map_err(ChannelCreationError::SetSecurityError)?;
+
+        let listener = socket.listen(1).map_err(ChannelCreationError::BindError)?;
+
+        let psm = listener
+            .as_ref()
+            .local_addr()
+            .map_err(ChannelCreationError::BindError)?
+            .psm;
+
+        Ok(Self {
+            listener,
+            psm,
+            adapter,
+            session,
+        })
+    }
+
+    pub fn psm(&self) -> u16 {
+        self.psm
+    }
+
+    pub async fn accept(&self) -> crate::Result<(Channel, Device)> {
+        let (stream, sa) = self
+            .listener
+            .accept()
+            .await
+            .map_err(ChannelCreationError::AcceptError)?;
+        let device = Device::new(self.session.clone(), &self.adapter, sa.addr).await?;
+        Ok((Channel::from_stream(stream), device))
     }
 }
 
 impl L2CapChannelImpl for Channel {
     fn split(self) -> (crate::L2CapReader, crate::L2CapWriter) {
-        self.stream.into_split()
+        let (reader, writer) = self.stream.into_split();
+        (crate::L2CapReader(reader), crate::L2CapWriter(writer))
+    }
+
+    fn psm(&self) -> crate::Result<u16> {
+        Ok(self.stream.peer_addr().map_err(io_error)?.psm)
+    }
+
+    fn peer(&self) -> crate::Result<DeviceId> {
+        Ok(DeviceId(self.stream.peer_addr().map_err(io_error)?.addr))
+    }
+
+    fn send_mtu(&self) -> crate::Result<usize> {
+        self.stream.as_ref().send_mtu().map(usize::from).map_err(io_error)
+    }
+
+    fn recv_mtu(&self) -> crate::Result<usize> {
+        self.stream.as_ref().recv_mtu().map(usize::from).map_err(io_error)
+    }
+
+    fn security(&self) -> crate::Result<L2CapSecurity> {
+        let security = self.stream.as_ref().security().map_err(io_error)?;
+        let level = match security.level {
+            bluer::l2cap::SecurityLevel::Sdp | bluer::l2cap::SecurityLevel::Low => SecurityLevel::Low,
+            bluer::l2cap::SecurityLevel::Medium => SecurityLevel::Medium,
+            bluer::l2cap::SecurityLevel::High => SecurityLevel::High,
+            bluer::l2cap::SecurityLevel::Fips => SecurityLevel::Fips,
+        };
+        Ok(L2CapSecurity {
+            level,
+            key_size: security.key_size,
+        })
+    }
+
+    fn flow_control(&self) -> crate::Result<L2CapFlowControl> {
+        match self.stream.as_ref().flow_control().map_err(io_error)? {
+            bluer::l2cap::FlowControl::Le => Ok(L2CapFlowControl::LeCredit),
+            bluer::l2cap::FlowControl::Extended => Ok(L2CapFlowControl::EnhancedCredit),
+        }
     }
 }
 
@@ -83,18 +266,24 @@ impl From<ChannelCreationError> for crate::Error {
     fn from(value: ChannelCreationError) -> Self {
         let kind = match &value {
             ChannelCreationError::SetSecurityError(_) => ErrorKind::Internal,
-            ChannelCreationError::ConnectionError(_) => ErrorKind::ConnectionFailed,
+            ChannelCreationError::ConnectionError(_) | ChannelCreationError::AcceptError(_) => {
+                ErrorKind::ConnectionFailed
+            }
+            ChannelCreationError::BindError(_) => ErrorKind::Other,
         };
         let message = match &value {
             ChannelCreationError::SetSecurityError(_) => "Error setting connection security level.",
             ChannelCreationError::ConnectionError(_) => "Error connecting to l2cap stream.",
+            ChannelCreationError::BindError(_) => "Error binding l2cap listener.",
+            ChannelCreationError::AcceptError(_) => "Error accepting l2cap stream.",
         };
         crate::Error::new(
             kind,
             match value {
-                ChannelCreationError::SetSecurityError(io) | ChannelCreationError::ConnectionError(io) => {
-                    Some(Box::new(io))
-                }
+                ChannelCreationError::SetSecurityError(io)
+                | ChannelCreationError::ConnectionError(io)
+                | ChannelCreationError::BindError(io)
+                | ChannelCreationError::AcceptError(io) => Some(Box::new(io)),
             },
             message.to_owned(),
         )
@@ -0,0 +1,87 @@
+//! Advertisement monitor patterns for passive scanning
+
+use bluer::monitor::Pattern;
+
+use crate::advertisement::{
+    COMPLETE_LIST_128_BIT_UUIDS, COMPLETE_LIST_16_BIT_UUIDS, COMPLETE_LIST_32_BIT_UUIDS, COMPLETE_LOCAL_NAME,
+    INCOMPLETE_LIST_128_BIT_UUIDS, INCOMPLETE_LIST_16_BIT_UUIDS, INCOMPLETE_LIST_32_BIT_UUIDS,
+    MANUFACTURER_SPECIFIC_DATA, SERVICE_DATA_128_BIT_UUID, SERVICE_DATA_16_BIT_UUID, SERVICE_DATA_32_BIT_UUID,
+    SHORTENED_LOCAL_NAME,
+};
+use crate::{BluetoothUuidExt, LocalNameFilter, ScanFilter, Uuid};
+
+/// The maximum length of the data of an AD structure in a legacy advertising packet
+const MAX_AD_DATA_LEN: usize = 29;
+
+/// Builds patterns matching at least the advertisements matched by `filter`
+///
+/// A monitor reports an advertisement if any of its patterns matches, so only the most selective criterion of `filter`
+/// is turned into patterns and all criteria are checked in software. Returns an empty list if `filter` has no
+/// criterion which can be expressed as a pattern.
+pub fn patterns(filter: &ScanFilter) -> Vec<Pattern> {
+    if let Some(data) = &filter.manufacturer_data {
+        let mut content = data.company_id.to_le_bytes().to_vec();
+        content.extend_from_slice(exact_prefix(&data.data, data.mask.as_deref()));
+        vec![pattern(MANUFACTURER_SPECIFIC_DATA, 0, content)]
+    } else if let Some(data) = &filter.service_data {
+        let mut content = uuid_to_le_bytes(&data.uuid);
+        let data_type = match content.len() {
+            2 => SERVICE_DATA_16_BIT_UUID,
+            4 => SERVICE_DATA_32_BIT_UUID,
+            _ => SERVICE_DATA_128_BIT_UUID,
+        };
+        content.extend_from_slice(exact_prefix(&data.data, data.mask.as_deref()));
+        vec![pattern(data_type, 0, content)]
+    } else if !filter.services.is_empty() {
+        filter.services.iter().flat_map(service_patterns).collect()
+    } else {
+        match &filter.local_name {
+            Some(LocalNameFilter::Exact(name) | LocalNameFilter::Prefix(name)) if !name.is_empty() => {
+                [SHORTENED_LOCAL_NAME, COMPLETE_LOCAL_NAME]
+                    .into_iter()
+                    .map(|data_type| pattern(data_type, 0, name.as_bytes().to_vec()))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Patterns matching `uuid` at any position of a list of service UUIDs
+fn service_patterns(uuid: &Uuid) -> Vec<Pattern> {
+    let content = uuid_to_le_bytes(uuid);
+    let data_types = match content.len() {
+        2 => [INCOMPLETE_LIST_16_BIT_UUIDS, COMPLETE_LIST_16_BIT_UUIDS],
+        4 => [INCOMPLETE_LIST_32_BIT_UUIDS, COMPLETE_LIST_32_BIT_UUIDS],
+        _ => [INCOMPLETE_LIST_128_BIT_UUIDS, COMPLETE_LIST_128_BIT_UUIDS],
+    };
+
+    (0..=MAX_AD_DATA_LEN - content.len())
+        .step_by(content.len())
+        .flat_map(|position| data_types.map(|data_type| pattern(data_type, position, content.clone())))
+        .collect()
+}
+
+fn pattern(data_type: u8, start_position: usize, mut content: Vec<u8>) -> Pattern {
+    content.truncate(MAX_AD_DATA_LEN - start_position);
+    Pattern {
+        data_type,
+        start_position: start_position as u8,
+        content,
+    }
+}
+
+/// The leading bytes of `data` which must match exactly according to `mask`
+fn exact_prefix<'a>(data: &'a [u8], mask: Option<&[u8]>) -> &'a [u8] {
+    let len = mask
+        .and_then(|mask| mask.iter().take(data.len()).position(|x| *x != 0xff))
+        .unwrap_or(data.len());
+    &data[..len]
+}
+
+/// AD structures encode UUIDs in little-endian byte order
+fn uuid_to_le_bytes(uuid: &Uuid) -> Vec<u8> {
+    let mut bytes = uuid.as_bluetooth_bytes().to_vec();
+    bytes.reverse();
+    bytes
+}
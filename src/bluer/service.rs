@@ -7,6 +7,8 @@ use crate::{Characteristic, Result, Service, Uuid};
 pub struct ServiceImpl {
     pub(super) inner: bluer::gatt::remote::Service,
     device: Arc<bluer::Device>,
+    uuid: Uuid,
+    is_primary: bool,
 }
 
 impl PartialEq for ServiceImpl {
@@ -28,38 +30,33 @@ impl std::hash::Hash for ServiceImpl {
 }
 
 impl Service {
-    pub(super) fn new(device: Arc<bluer::Device>, inner: bluer::gatt::remote::Service) -> Service {
-        Service(ServiceImpl { inner, device })
+    pub(super) async fn new(device: Arc<bluer::Device>, inner: bluer::gatt::remote::Service) -> Result<Service> {
+        // These attributes never change, so read them once to allow synchronous access
+        let uuid = inner.uuid().await?;
+        let is_primary = inner.primary().await?;
+        Ok(Service(ServiceImpl {
+            inner,
+            device,
+            uuid,
+            is_primary,
+        }))
     }
 }
 
 impl ServiceImpl {
     /// The [`Uuid`] identifying the type of this GATT service
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if there is a current Tokio runtime and it is single-threaded, if there is no current
-    /// Tokio runtime and creating one fails, or if the underlying [`ServiceImpl::uuid_async()`] method fails.
     pub fn uuid(&self) -> Uuid {
-        // Call an async function from a synchronous context
-        match tokio::runtime::Handle::try_current() {
-            Ok(handle) => tokio::task::block_in_place(move || handle.block_on(self.uuid_async())),
-            Err(_) => tokio::runtime::Builder::new_current_thread()
-                .build()
-                .unwrap()
-                .block_on(self.uuid_async()),
-        }
-        .unwrap()
+        self.uuid
     }
 
     /// The [`Uuid`] identifying the type of this GATT service
     pub async fn uuid_async(&self) -> Result<Uuid> {
-        self.inner.uuid().await.map_err(Into::into)
+        Ok(self.uuid)
     }
 
     /// Whether this is a primary service of the device.
     pub async fn is_primary(&self) -> Result<bool> {
-        self.inner.primary().await.map_err(Into::into)
+        Ok(self.is_primary)
     }
 
     /// Discover all characteristics associated with this service.
@@ -81,11 +78,12 @@ impl ServiceImpl {
     ///
     /// If no characteristics have been discovered yet, this method will perform characteristic discovery.
     pub async fn characteristics(&self) -> Result<Vec<Characteristic>> {
-        self.inner
-            .characteristics()
-            .await
-            .map_err(Into::into)
-            .map(|x| x.into_iter().map(Characteristic::new).collect())
+        let characteristics = self.inner.characteristics().await?;
+        let mut res = Vec::with_capacity(characteristics.len());
+        for characteristic in characteristics {
+            res.push(Characteristic::new(characteristic).await?);
+        }
+        Ok(res)
     }
 
     /// Discover the included services of this service.
@@ -110,7 +108,7 @@ impl ServiceImpl {
         let includes = self.inner.includes().await?;
         let mut res = Vec::with_capacity(includes.len());
         for id in includes {
-            res.push(Service::new(self.device.clone(), self.device.service(id).await?));
+            res.push(Service::new(self.device.clone(), self.device.service(id).await?).await?);
         }
         Ok(res)
     }
@@ -8,12 +8,6 @@ pub struct Characteristic(pub(crate) sys::characteristic::CharacteristicImpl);
 
 impl Characteristic {
     /// The [`Uuid`] identifying the type of this GATT characteristic
-    ///
-    /// # Panics
-    ///
-    /// On Linux, this method will panic if there is a current Tokio runtime and it is single-threaded, if there is no
-    /// current Tokio runtime and creating one fails, or if the underlying [`Characteristic::uuid_async()`] method
-    /// fails.
     #[inline]
     pub fn uuid(&self) -> Uuid {
         self.0.uuid()
@@ -62,6 +56,11 @@ impl Characteristic {
     }
 
     /// Get the maximum amount of data that can be written in a single packet for this characteristic.
+    ///
+    /// # Platform specific
+    ///
+    /// On Linux, this is based on the MTU when the characteristic was discovered or last queried with
+    /// [`max_write_len_async`][Self::max_write_len_async].
     #[inline]
     pub fn max_write_len(&self) -> Result<usize> {
         self.0.max_write_len()
@@ -81,6 +80,12 @@ impl Characteristic {
         self.0.notify().await
     }
 
+    /// Like [`notify`][Self::notify], but the returned stream owns the characteristic.
+    #[inline]
+    pub(crate) async fn notify_owned(self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + 'static> {
+        self.0.notify_owned().await
+    }
+
     /// Is the device currently sending notifications for this characteristic?
     #[inline]
     pub async fn is_notifying(&self) -> Result<bool> {
@@ -2,6 +2,7 @@
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures_core::Stream;
 use futures_lite::{stream, StreamExt};
@@ -10,12 +11,14 @@ use objc_id::ShareId;
 use tracing::{debug, error, info, warn};
 
 use super::delegates::{self, CentralDelegate};
+use super::error::disconnect_reason;
 use super::types::{CBCentralManager, CBManagerAuthorization, CBManagerState, CBUUID, NSUUID};
-use crate::corebluetooth::types::{dispatch_get_global_queue, QOS_CLASS_UTILITY};
+use crate::corebluetooth::types::{dispatch_get_global_queue, scan_options, QOS_CLASS_UTILITY};
 use crate::error::ErrorKind;
 use crate::util::defer;
 use crate::{
-    AdapterEvent, AdvertisementData, AdvertisingDevice, ConnectionEvent, Device, DeviceId, Error, Result, Uuid,
+    AdapterEvent, AdvertisementData, AdvertisingDevice, ConnectionEvent, Device, DeviceEvent, DeviceId,
+    DuplicatePolicy, Error, Result, ScanFilter, ScanOptions, Uuid,
 };
 
 /// The system's Bluetooth adapter interface.
@@ -79,6 +82,95 @@ impl AdapterImpl {
         })
     }
 
+    /// Creates interfaces to all Bluetooth adapters of the system
+    ///
+    /// Only the default adapter is available on MacOS/iOS.
+    pub async fn all() -> Result<Vec<Self>> {
+        Ok(Self::default().await.into_iter().collect())
+    }
+
+    /// Creates an interface to the Bluetooth adapter with the given name
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn by_name(_name: &str) -> Result<Self> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The system name of this adapter, which uniquely identifies it
+    ///
+    /// Not supported on MacOS/iOS.
+    pub fn name(&self) -> Result<String> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The Bluetooth address of this adapter, most significant byte first
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn address(&self) -> Result<[u8; 6]> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// The human readable name of this adapter, which is shown to remote devices
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn alias(&self) -> Result<String> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether this adapter is powered on
+    pub async fn is_powered(&self) -> Result<bool> {
+        Ok(self.central.state() == CBManagerState::POWERED_ON)
+    }
+
+    /// Whether this adapter is discoverable by remote devices
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn is_discoverable(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether this adapter accepts pairing requests from remote devices
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn is_pairable(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Powers this adapter on or off
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn set_powered(&self, _powered: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Makes this adapter discoverable by remote devices, or stops it from being discoverable
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn set_discoverable(&self, _discoverable: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Sets how long this adapter stays discoverable after `set_discoverable` is called
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn set_discoverable_timeout(&self, _timeout: Duration) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Allows or disallows pairing requests from remote devices
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn set_pairable(&self, _pairable: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Sets how long this adapter stays pairable after `set_pairable` is called
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn set_pairable_timeout(&self, _timeout: Duration) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
     pub async fn events(&self) -> Result<impl Stream<Item = Result<AdapterEvent>> + Send + Unpin + '_> {
         let receiver = self.delegate.sender().new_receiver();
@@ -134,6 +226,20 @@ impl AdapterImpl {
             .await
     }
 
+    /// Finds all devices which are paired with the system
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn paired_devices(&self) -> Result<Vec<Device>> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Finds all devices known to the system
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn known_devices(&self) -> Result<Vec<Device>> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Finds all connected devices providing any service in `services`
     ///
     /// # Panics
@@ -165,6 +271,31 @@ impl AdapterImpl {
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
+        self.scan_with_filter(ScanFilter::with_services(services)).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter`.
+    ///
+    /// The services of `filter` are applied by CoreBluetooth. All other criteria are checked in software.
+    pub async fn scan_with_filter(
+        &self,
+        filter: ScanFilter,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        self.scan_with_options(filter, ScanOptions::default()).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter` using the given `options`.
+    ///
+    /// CoreBluetooth always scans actively, so [`ScanMode::Passive`][crate::ScanMode::Passive] returns an error.
+    /// [`DuplicatePolicy::ReportChanges`] is implemented in software on top of a scan allowing duplicates. Only
+    /// Bluetooth LE devices are discovered.
+    pub async fn scan_with_options(
+        &self,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        options.check_mode(false)?;
+
         if self.central.state() != CBManagerState::POWERED_ON {
             return Err(ErrorKind::AdapterUnavailable.into());
         }
@@ -173,8 +304,13 @@ impl AdapterImpl {
             return Err(ErrorKind::AlreadyScanning.into());
         }
 
-        let services = (!services.is_empty()).then(|| {
-            let vec = services.iter().copied().map(CBUUID::from_uuid).collect::<Vec<_>>();
+        let services = (!filter.services.is_empty()).then(|| {
+            let vec = filter
+                .services
+                .iter()
+                .copied()
+                .map(CBUUID::from_uuid)
+                .collect::<Vec<_>>();
             NSArray::from_vec(vec)
         });
 
@@ -202,10 +338,16 @@ impl AdapterImpl {
                     }),
                     _ => None,
                 }
-            });
+            })
+            .filter(move |x| filter.matches(x) && options.matches(x))
+            .filter(options.duplicate_filter());
 
+        let scan_options = match options.duplicates {
+            DuplicatePolicy::PlatformDefault => None,
+            DuplicatePolicy::ReportAll | DuplicatePolicy::ReportChanges => Some(scan_options(true)),
+        };
         self.central
-            .scan_for_peripherals_with_services(services.as_deref(), None);
+            .scan_for_peripherals_with_services(services.as_deref(), scan_options.as_deref());
 
         Ok(events)
     }
@@ -368,10 +510,12 @@ impl AdapterImpl {
                     {
                         Some(ConnectionEvent::Connected)
                     }
-                    delegates::CentralEvent::Disconnect { peripheral, .. }
+                    delegates::CentralEvent::Disconnect { peripheral, error }
                         if peripheral.identifier() == device.0.peripheral.identifier() =>
                     {
-                        Some(ConnectionEvent::Disconnected)
+                        Some(ConnectionEvent::Disconnected {
+                            reason: disconnect_reason(error.as_deref()),
+                        })
                     }
                     _ => None,
                 }
@@ -397,10 +541,44 @@ impl AdapterImpl {
                 delegates::CentralEvent::Connect { peripheral } if peripheral == device.0.peripheral => {
                     Some(ConnectionEvent::Connected)
                 }
-                delegates::CentralEvent::Disconnect { peripheral, .. } if peripheral == device.0.peripheral => {
-                    Some(ConnectionEvent::Disconnected)
+                delegates::CentralEvent::Disconnect { peripheral, error } if peripheral == device.0.peripheral => {
+                    Some(ConnectionEvent::Disconnected {
+                        reason: disconnect_reason(error.as_deref()),
+                    })
+                }
+                _ => None,
+            }))
+    }
+
+    /// Monitors all devices known to the adapter for changes.
+    ///
+    /// Only connection and disconnection events for devices connected with [`connect_device`][Self::connect_device]
+    /// are reported.
+    pub async fn device_events(&self) -> Result<impl Stream<Item = Result<DeviceEvent>> + Send + Unpin + '_> {
+        let events = self.delegate.sender().new_receiver();
+        Ok(events
+            .take_while(|_| self.central.state() == CBManagerState::POWERED_ON)
+            .filter_map(|x| match x {
+                delegates::CentralEvent::Connect { peripheral } => {
+                    Some(Ok(DeviceEvent::Connected(Device::new(peripheral))))
                 }
+                delegates::CentralEvent::Disconnect { peripheral, error } => Some(Ok(DeviceEvent::Disconnected {
+                    device: Device::new(peripheral),
+                    reason: disconnect_reason(error.as_deref()),
+                })),
                 _ => None,
             }))
     }
+
+    /// Listen for L2CAP connection-oriented channels opened by remote devices
+    ///
+    /// Not supported on MacOS/iOS.
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(
+        &self,
+        _psm: Option<u16>,
+        _security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::Listener> {
+        Err(ErrorKind::NotSupported.into())
+    }
 }
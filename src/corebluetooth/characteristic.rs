@@ -172,6 +172,11 @@ impl CharacteristicImpl {
     ///
     /// Returns a stream of values for the characteristic sent from the device.
     pub async fn notify(&self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + '_> {
+        self.clone().notify_owned().await
+    }
+
+    /// Like [`notify`][Self::notify], but the returned stream owns the characteristic.
+    pub async fn notify_owned(self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + 'static> {
         let properties = self.properties().await?;
         if !(properties.notify || properties.indicate) {
             return Err(Error::new(
@@ -190,9 +195,10 @@ impl CharacteristicImpl {
         }
 
         peripheral.set_notify(&self.inner, true);
+        let inner = self.inner.clone();
         let guard = defer(move || {
-            let peripheral = self.inner.service().peripheral();
-            peripheral.set_notify(&self.inner, false);
+            let peripheral = inner.service().peripheral();
+            peripheral.set_notify(&inner, false);
         });
 
         loop {
@@ -215,13 +221,12 @@ impl CharacteristicImpl {
             }
         }
 
+        let inner = self.inner.clone();
         let updates = receiver
             .filter_map(move |x| {
                 let _guard = &guard;
                 match x {
-                    PeripheralEvent::CharacteristicValueUpdate { characteristic, error }
-                        if characteristic == self.inner =>
-                    {
+                    PeripheralEvent::CharacteristicValueUpdate { characteristic, error } if characteristic == inner => {
                         match error {
                             Some(err) => Some(Err(Error::from_nserror(err))),
                             None => Some(Ok(())),
@@ -239,9 +244,10 @@ impl CharacteristicImpl {
                 }
             })
             .then(move |x| {
+                let this = self.clone();
                 Box::pin(async move {
                     match x {
-                        Ok(_) => self.value().await,
+                        Ok(_) => this.value().await,
                         Err(err) => Err(err),
                     }
                 })
@@ -9,7 +9,7 @@ use objc_id::{Id, ShareId, Shared};
 use tracing::{debug, error};
 
 use super::types::{id, CBCharacteristic, CBDescriptor, CBL2CAPChannel, CBPeripheral, CBService, NSError, NSInteger};
-use crate::ConnectionEvent;
+use crate::{ConnectionEvent, DisconnectReason};
 
 #[derive(Clone)]
 pub enum CentralEvent {
@@ -130,7 +130,9 @@ pub enum CBConnectionEvent {
 impl From<CBConnectionEvent> for ConnectionEvent {
     fn from(value: CBConnectionEvent) -> Self {
         match value {
-            CBConnectionEvent::Disconnected => ConnectionEvent::Disconnected,
+            CBConnectionEvent::Disconnected => ConnectionEvent::Disconnected {
+                reason: DisconnectReason::Unknown,
+            },
             CBConnectionEvent::Connected => ConnectionEvent::Connected,
         }
     }
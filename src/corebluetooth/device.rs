@@ -11,8 +11,8 @@ use super::l2cap_channel::Channel;
 use super::types::{CBPeripheral, CBPeripheralState, CBService, CBUUID};
 use crate::device::ServicesChanged;
 use crate::error::ErrorKind;
-use crate::pairing::PairingAgent;
-use crate::{Device, DeviceId, Error, Result, Service, Uuid};
+use crate::pairing::{PairingAgent, PairingResult};
+use crate::{Device, DeviceId, DeviceProperty, Error, Result, SecurityLevel, Service, Uuid};
 
 /// A Bluetooth LE device
 #[derive(Clone)]
@@ -83,6 +83,10 @@ impl DeviceImpl {
         self.name()
     }
 
+    pub async fn alias(&self) -> Result<String> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// The connection status for this device
     pub async fn is_connected(&self) -> bool {
         self.peripheral.state() == CBPeripheralState::CONNECTED
@@ -96,17 +100,21 @@ impl DeviceImpl {
     /// Attempt to pair this device using the system default pairing UI
     ///
     /// Device pairing is performed automatically by the OS when a characteristic requiring security is accessed. This
-    /// method is a no-op.
-    pub async fn pair(&self) -> Result<()> {
-        Ok(())
+    /// method is a no-op and reports an unbonded result.
+    pub async fn pair(&self) -> Result<PairingResult> {
+        Ok(PairingResult {
+            bonded: false,
+            method: None,
+            security_level: None,
+        })
     }
 
     /// Attempt to pair this device using the system default pairing UI
     ///
     /// Device pairing is performed automatically by the OS when a characteristic requiring security is accessed. This
-    /// method is a no-op.
-    pub async fn pair_with_agent<T: PairingAgent>(&self, _agent: &T) -> Result<()> {
-        Ok(())
+    /// method is a no-op and reports an unbonded result.
+    pub async fn pair_with_agent<T: PairingAgent>(&self, _agent: &T) -> Result<PairingResult> {
+        self.pair().await
     }
 
     /// Disconnect and unpair this device from the system
@@ -118,6 +126,20 @@ impl DeviceImpl {
         Err(ErrorKind::NotSupported.into())
     }
 
+    /// The security level of the current connection to this device
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn security_level(&self) -> Result<SecurityLevel> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether the current connection to this device is encrypted
+    ///
+    /// Not supported on MacOS/iOS.
+    pub async fn is_encrypted(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// Discover the primary services of this device.
     pub async fn discover_services(&self) -> Result<Vec<Service>> {
         self.discover_services_inner(None).await
@@ -201,7 +223,23 @@ impl DeviceImpl {
         }))
     }
 
+    /// Monitors the device for changes of its properties.
+    ///
+    /// Only name changes and RSSI values read with [`rssi`][Self::rssi] are reported.
+    pub async fn property_changes(&self) -> Result<impl Stream<Item = DeviceProperty> + Send + Unpin + '_> {
+        let receiver = self.delegate.sender().new_receiver();
+        Ok(receiver.filter_map(|ev| match ev {
+            PeripheralEvent::NameUpdate => self.name().ok().map(DeviceProperty::Name),
+            PeripheralEvent::ReadRssi { rssi, error: None } => Some(DeviceProperty::Rssi(rssi)),
+            _ => None,
+        }))
+    }
+
     /// Get the current signal strength from the device in dBm.
+    pub async fn refresh_rssi(&self) -> Result<i16> {
+        self.rssi().await
+    }
+
     pub async fn rssi(&self) -> Result<i16> {
         let mut receiver = self.delegate.sender().new_receiver();
         self.peripheral.read_rssi();
@@ -217,8 +255,8 @@ impl DeviceImpl {
     }
 
     #[cfg(feature = "l2cap")]
-    pub async fn open_l2cap_channel(&self, psm: u16, secure: bool) -> Result<Channel> {
-        if secure {
+    pub async fn open_l2cap_channel(&self, psm: u16, security: crate::L2CapSecurity) -> Result<Channel> {
+        if security.level > crate::SecurityLevel::Low {
             return Err(Error::new(
                 ErrorKind::NotSupported,
                 None,
@@ -237,7 +275,7 @@ impl DeviceImpl {
                 PeripheralEvent::L2CAPChannelOpened {
                     channel: Some(chan),
                     error: None,
-                } => return Channel::new(chan),
+                } => return Channel::new(chan, self.id()),
                 PeripheralEvent::L2CAPChannelOpened {
                     channel: None,
                     error: ns_error,
@@ -252,6 +290,18 @@ impl DeviceImpl {
             }
         }
     }
+
+    /// Open an L2CAP connection-oriented channel in sequential packet mode
+    ///
+    /// Not supported on MacOS/iOS.
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_packet_channel(
+        &self,
+        _psm: u16,
+        _security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::PacketChannel> {
+        Err(ErrorKind::NotSupported.into())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -4,6 +4,7 @@ use objc_id::ShareId;
 use super::types::CBError;
 pub use super::types::NSError;
 use crate::error::{AttError, ErrorKind};
+use crate::DisconnectReason;
 
 impl crate::Error {
     pub(super) fn from_recv_error(err: async_broadcast::RecvError) -> Self {
@@ -61,6 +62,21 @@ fn kind_from_nserror(value: &NSError) -> ErrorKind {
     }
 }
 
+/// Determines why a peripheral disconnected from the error passed to `centralManager:didDisconnectPeripheral:error:`.
+///
+/// CoreBluetooth reports no error if the connection was cancelled by the app.
+pub(super) fn disconnect_reason(err: Option<&NSError>) -> DisconnectReason {
+    match err {
+        None => DisconnectReason::LocalHost,
+        Some(err) if err.domain().as_str() == "CBErrorDomain" => match CBError(err.code()) {
+            CBError::CONNECTION_TIMEOUT => DisconnectReason::LinkLoss,
+            CBError::PERIPHERAL_DISCONNECTED => DisconnectReason::RemoteDevice,
+            _ => DisconnectReason::Unknown,
+        },
+        Some(_) => DisconnectReason::Unknown,
+    }
+}
+
 struct NSErrorError(ShareId<NSError>);
 
 impl std::fmt::Debug for NSErrorError {
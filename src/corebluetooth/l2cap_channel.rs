@@ -1,21 +1,19 @@
-use std::{
-    io::Result,
-    mem::ManuallyDrop,
-    os::fd::{FromRawFd, IntoRawFd, RawFd},
-    pin::Pin,
-    task::{Context, Poll},
-};
+use std::io::Result;
+use std::mem::ManuallyDrop;
+use std::os::fd::{FromRawFd, IntoRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 use objc_foundation::INSData;
 use objc_id::{Id, Shared};
-use tokio::{
-    io::{AsyncRead, AsyncWrite, ReadBuf, ReadHalf, WriteHalf},
-    net::UnixStream,
-};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf, ReadHalf, WriteHalf};
+use tokio::net::UnixStream;
 use tracing::warn;
 
 use super::types::{kCFStreamPropertySocketNativeHandle, CBL2CAPChannel, CFStream};
-use crate::{error::ErrorKind, Error, L2CapChannelImpl};
+use super::DeviceId;
+use crate::error::ErrorKind;
+use crate::{Error, L2CapChannelImpl, L2CapFlowControl, L2CapSecurity};
 
 // This implementation is based upon the fact that that CBL2CAPChannel::outputStream -> an NS Output Stream; (https://developer.apple.com/documentation/foundation/outputstream)
 // NS Output stream is toll free bridged to CFWriteStream (https://developer.apple.com/documentation/corefoundation/cfwritestream)
@@ -29,9 +27,10 @@ use crate::{error::ErrorKind, Error, L2CapChannelImpl};
 
 #[derive(Debug)]
 pub struct Channel {
-    _channel: Id<CBL2CAPChannel, Shared>,
+    channel: Id<CBL2CAPChannel, Shared>,
+    peer: DeviceId,
 
-    /// the _channel object owns the file descriptor, so we have a ManuallyDrop here to prevent the file descriptor from
+    /// the channel object owns the file descriptor, so we have a ManuallyDrop here to prevent the file descriptor from
     /// being double closed when this object is dropped.
     stream: ManuallyDrop<Pin<Box<UnixStream>>>,
 }
@@ -45,7 +44,7 @@ enum ChannelCreationError {
 }
 
 impl Channel {
-    pub fn new(channel: Id<CBL2CAPChannel, Shared>) -> crate::Result<Self> {
+    pub fn new(channel: Id<CBL2CAPChannel, Shared>, peer: DeviceId) -> crate::Result<Self> {
         let input_stream = channel.input_stream();
         let output_stream = channel.output_stream();
 
@@ -100,10 +99,7 @@ impl Channel {
 
         let stream = ManuallyDrop::new(Box::pin(tokio_stream));
 
-        Ok(Channel {
-            _channel: channel,
-            stream,
-        })
+        Ok(Channel { channel, peer, stream })
     }
 }
 impl AsyncRead for Channel {
@@ -129,7 +125,7 @@ impl AsyncWrite for Channel {
 impl Drop for Channel {
     fn drop(&mut self) {
         // Manually deconstruct the tokio stream to a file descriptor to make sure it is not closed on drop.
-        // The file descriptor is closed when _channel is dropped.
+        // The file descriptor is closed when channel is dropped.
 
         // Safe as this is the drop impl, so the stream will not be used after this.
         let stream = unsafe { ManuallyDrop::take(&mut self.stream) };
@@ -175,6 +171,67 @@ pub type Writer = WriteHalf<Channel>;
 
 impl L2CapChannelImpl for Channel {
     fn split(self) -> (crate::L2CapReader, crate::L2CapWriter) {
-        tokio::io::split(self)
+        let (reader, writer) = tokio::io::split(self);
+        (crate::L2CapReader(reader), crate::L2CapWriter(writer))
+    }
+
+    fn psm(&self) -> crate::Result<u16> {
+        Ok(self.channel.psm())
+    }
+
+    fn peer(&self) -> crate::Result<DeviceId> {
+        Ok(self.peer.clone())
+    }
+
+    fn send_mtu(&self) -> crate::Result<usize> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn recv_mtu(&self) -> crate::Result<usize> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn security(&self) -> crate::Result<L2CapSecurity> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn flow_control(&self) -> crate::Result<L2CapFlowControl> {
+        Err(ErrorKind::NotSupported.into())
+    }
+}
+
+/// Listening for L2CAP channels is not supported on MacOS/iOS, so no listener is ever created.
+#[derive(Debug)]
+pub enum Listener {}
+
+impl Listener {
+    pub fn psm(&self) -> u16 {
+        match *self {}
+    }
+
+    pub async fn accept(&self) -> crate::Result<(Channel, crate::Device)> {
+        match *self {}
+    }
+}
+
+/// Sequential packet mode is not supported on MacOS/iOS, so no packet channel is ever created.
+#[derive(Debug)]
+pub enum PacketChannel {}
+
+impl PacketChannel {
+    pub async fn send(&self, _packet: &[u8]) -> crate::Result<()> {
+        match *self {}
+    }
+
+    pub async fn recv(&self) -> crate::Result<Vec<u8>> {
+        match *self {}
+    }
+
+    pub fn send_mtu(&self) -> crate::Result<usize> {
+        match *self {}
+    }
+
+    pub fn recv_mtu(&self) -> crate::Result<usize> {
+        match *self {}
     }
 }
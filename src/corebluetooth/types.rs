@@ -9,7 +9,7 @@ use std::os::raw::{c_char, c_void};
 
 use objc::rc::autoreleasepool;
 use objc::runtime::{Object, BOOL, NO};
-use objc::{msg_send, sel, sel_impl, Message};
+use objc::{class, msg_send, sel, sel_impl, Message};
 use objc_foundation::{
     object_struct, INSData, INSDictionary, INSFastEnumeration, INSObject, INSString, NSArray, NSData, NSDictionary,
     NSObject, NSString,
@@ -211,13 +211,26 @@ impl AdvertisementData {
             .map(CBUUID::to_uuid)
             .collect();
 
+        let solicited_services = adv_data
+            .object_for(unsafe { extern_nsstring(CBAdvertisementDataSolicitedServiceUUIDsKey) })
+            .into_iter()
+            .flat_map(|x| {
+                let val: &NSArray<CBUUID> = unsafe { &*(x as *const NSObject).cast() };
+                val.enumerator()
+            })
+            .map(CBUUID::to_uuid)
+            .collect();
+
         AdvertisementData {
             local_name,
+            all_manufacturer_data: manufacturer_data.iter().cloned().collect(),
             manufacturer_data,
             services,
+            solicited_services,
             service_data,
             tx_power_level,
             is_connectable,
+            ..Default::default()
         }
     }
 }
@@ -236,9 +249,12 @@ extern "C" {
     static CBAdvertisementDataServiceDataKey: id;
     static CBAdvertisementDataServiceUUIDsKey: id;
     static CBAdvertisementDataOverflowServiceUUIDsKey: id;
+    static CBAdvertisementDataSolicitedServiceUUIDsKey: id;
     static CBAdvertisementDataTxPowerLevelKey: id;
     static CBAdvertisementDataIsConnectable: id;
-    static CBAdvertisementDataSolicitedServiceUUIDsKey: id;
+
+    // CBCentralManagerScanOption keys
+    static CBCentralManagerScanOptionAllowDuplicatesKey: id;
 
     // CBConnectionEventMatchingOption
     static CBConnectionEventMatchingOptionPeripheralUUIDs: id;
@@ -271,6 +287,17 @@ unsafe fn extern_nsstring(ptr: id) -> &'static NSString {
     &*(ptr as *const NSString)
 }
 
+pub fn scan_options(allow_duplicates: bool) -> Id<NSDictionary<NSString, NSObject>> {
+    autoreleasepool(move || unsafe {
+        let allow_duplicates: Id<NSObject> =
+            Id::from_ptr(msg_send![class!(NSNumber), numberWithBool: allow_duplicates as BOOL]);
+        NSDictionary::from_keys_and_objects(
+            &[extern_nsstring(CBCentralManagerScanOptionAllowDuplicatesKey)],
+            vec![allow_duplicates],
+        )
+    })
+}
+
 pub fn connection_event_matching_option_peripheral_uuids() -> &'static NSString {
     unsafe { extern_nsstring(CBConnectionEventMatchingOptionPeripheralUUIDs) }
 }
@@ -590,6 +617,9 @@ impl CBDescriptor {
 }
 
 impl CBL2CAPChannel {
+    pub fn psm(&self) -> u16 {
+        unsafe { msg_send![self, PSM] }
+    }
     pub fn input_stream(&self) -> ShareId<NSInputStream> {
         autoreleasepool(move || unsafe { ShareId::from_ptr(msg_send![self, inputStream]) })
     }
@@ -1,13 +1,17 @@
 #![allow(clippy::let_unit_value)]
 
+use std::collections::HashMap;
+use std::time::Duration;
+
 use futures_core::Stream;
-use futures_lite::StreamExt;
+use futures_lite::{future, StreamExt};
 
 use crate::error::ErrorKind;
-use crate::pairing::PairingAgent;
+use crate::pairing::{PairingAgent, PairingResult};
+use crate::util::sleep;
+use crate::{sys, DeviceId, Error, ManufacturerData, Result, SecurityLevel, Service, Uuid};
 #[cfg(feature = "l2cap")]
-use crate::L2CapChannel;
-use crate::{sys, DeviceId, Error, Result, Service, Uuid};
+use crate::{L2CapChannel, L2CapPacketChannel, L2CapSecurity};
 
 /// A Bluetooth LE device
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -31,10 +35,11 @@ impl Device {
     ///
     /// This can either be a name advertised or read from the device, or a name assigned to the device by the OS.
     ///
-    /// # Panics
+    /// # Platform specific
     ///
-    /// On Linux, this method will panic if there is a current Tokio runtime and it is single-threaded or if there is
-    /// no current Tokio runtime and creating one fails.
+    /// On Linux, this returns the name when the device was created or last queried with
+    /// [`name_async`][Self::name_async], which should be preferred to get the current name. Like
+    /// [`name_async`][Self::name_async], it is the same as the [`alias`][Self::alias].
     #[inline]
     pub fn name(&self) -> Result<String> {
         self.0.name()
@@ -43,11 +48,30 @@ impl Device {
     /// The local name for this device, if available
     ///
     /// This can either be a name advertised or read from the device, or a name assigned to the device by the OS.
+    ///
+    /// # Platform specific
+    ///
+    /// On Linux, this is the same as the [`alias`][Self::alias]. The name advertised by the device is reported in
+    /// [`AdvertisementData::local_name`][crate::AdvertisementData::local_name].
     #[inline]
     pub async fn name_async(&self) -> Result<String> {
         self.0.name_async().await
     }
 
+    /// The name assigned to this device by the OS
+    ///
+    /// # Platform specific
+    ///
+    /// On Linux, this is the alias of the device in BlueZ, which may have been set by the user and otherwise falls
+    /// back to the name of the device or its address.
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android. The mock
+    /// backend returns the name of the device.
+    #[inline]
+    pub async fn alias(&self) -> Result<String> {
+        self.0.alias().await
+    }
+
     /// The connection status for this device
     #[inline]
     pub async fn is_connected(&self) -> bool {
@@ -62,29 +86,43 @@ impl Device {
 
     /// Attempt to pair this device using the system default pairing UI
     ///
+    /// Returns how the device was paired. If the device was already paired, the
+    /// [`method`][PairingResult::method] of the result is `None`.
+    ///
     /// # Platform specific
     ///
     /// ## MacOS/iOS
     ///
     /// Device pairing is performed automatically by the OS when a characteristic requiring security is accessed. This
-    /// method is a no-op.
+    /// method is a no-op and the result reports the device as not bonded.
     ///
     /// ## Windows
     ///
     /// This will fail unless it is called from a UWP application.
+    ///
+    /// ## Linux/Android
+    ///
+    /// The association model and security level are not reported. On Linux, whether the device was bonded is read from
+    /// BlueZ.
     #[inline]
-    pub async fn pair(&self) -> Result<()> {
+    pub async fn pair(&self) -> Result<PairingResult> {
         self.0.pair().await
     }
 
     /// Attempt to pair this device using the system default pairing UI
     ///
+    /// Returns how the device was paired. If the device was already paired, the
+    /// [`method`][PairingResult::method] of the result is `None`.
+    ///
     /// # Platform specific
     ///
     /// On MacOS/iOS, device pairing is performed automatically by the OS when a characteristic requiring security is
-    /// accessed. This method is a no-op.
+    /// accessed. This method is a no-op and the result reports the device as not bonded.
+    ///
+    /// On Linux, the association model is inferred from the agent methods called by BlueZ, whether the device was bonded
+    /// is read from BlueZ and the security level is not reported.
     #[inline]
-    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, agent: &T) -> Result<()> {
+    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, agent: &T) -> Result<PairingResult> {
         self.0.pair_with_agent(agent).await
     }
 
@@ -98,6 +136,32 @@ impl Device {
         self.0.unpair().await
     }
 
+    /// The security level of the current connection to this device
+    ///
+    /// # Platform specific
+    ///
+    /// On Windows, this is the protection level the device was paired with, or [`SecurityLevel::Low`] if the device
+    /// is not paired. Windows does not report whether the level is [`SecurityLevel::Fips`].
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Linux, MacOS/iOS and Android.
+    #[inline]
+    pub async fn security_level(&self) -> Result<SecurityLevel> {
+        self.0.security_level().await
+    }
+
+    /// Whether the current connection to this device is encrypted
+    ///
+    /// # Platform specific
+    ///
+    /// On Windows, this reports whether the device is connected and paired, since Windows encrypts connections to
+    /// paired devices.
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Linux, MacOS/iOS and Android.
+    #[inline]
+    pub async fn is_encrypted(&self) -> Result<bool> {
+        self.0.is_encrypted().await
+    }
+
     /// Discover the primary services of this device.
     #[inline]
     pub async fn discover_services(&self) -> Result<Vec<Service>> {
@@ -149,21 +213,166 @@ impl Device {
     ///
     /// # Platform specific
     ///
-    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Windows and Linux.
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Windows.
+    ///
+    /// On Linux, returns the RSSI of the most recent advertisement received from the device, or an error of kind
+    /// [`NotFound`][crate::error::ErrorKind::NotFound] if none is known. BlueZ cannot read the RSSI of a connection,
+    /// so if the device is connected and no value is known an error of kind
+    /// [`NotSupported`][crate::error::ErrorKind::NotSupported] is returned. Use [`refresh_rssi`][Self::refresh_rssi]
+    /// to wait for a new value.
     #[inline]
     pub async fn rssi(&self) -> Result<i16> {
         self.0.rssi().await
     }
 
-    /// Open an L2CAP connection-oriented channel (CoC) to this device.
+    /// Get an up to date signal strength from the device in dBm, waiting at most `timeout` for it.
+    ///
+    /// Returns an error of kind [`Timeout`][crate::error::ErrorKind::Timeout] if no value was received in time.
     ///
     /// # Platform specific
     ///
     /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Windows.
+    ///
+    /// On MacOS/iOS and Android, this just calls [`rssi`][Self::rssi] and `timeout` is only applied to that call. On
+    /// MacOS/iOS that reads the RSSI of the connection, while on Android it is the value last reported by the system.
+    ///
+    /// On Linux, discovery is started and this method waits until the device's RSSI changes, which requires the device
+    /// to advertise. A discovery filter set by a running scan is left in place, so if it excludes the device this
+    /// method times out. BlueZ cannot read the RSSI of a connection, so an error of kind
+    /// [`NotSupported`][crate::error::ErrorKind::NotSupported] is returned immediately for connected devices.
+    pub async fn refresh_rssi(&self, timeout: Duration) -> Result<i16> {
+        let timeout = async {
+            sleep(timeout).await;
+            Err(Error::new(
+                ErrorKind::Timeout,
+                None,
+                "waiting for the signal strength of the device".to_string(),
+            ))
+        };
+        future::or(self.0.refresh_rssi(), timeout).await
+    }
+
+    /// Monitors the device for changes of its properties.
+    ///
+    /// # Platform specific
+    ///
+    /// | Property                              | MacOS/iOS | Windows | Linux | Android |
+    /// | ------------------------------------- | :-------: | :-----: | :---: | :-----: |
+    /// | `Name`                                |    ✅     |   ✅    |  ✅   |   ❌    |
+    /// | `Rssi`                                |    ⚠️     |   ❌    |  ✅   |   ❌    |
+    /// | `TxPower`                             |    ❌     |   ❌    |  ✅   |   ❌    |
+    /// | `Paired`/`Trusted`/`ServicesResolved` |    ❌     |   ❌    |  ✅   |   ❌    |
+    /// | `ManufacturerData`/`ServiceData`      |    ❌     |   ❌    |  ✅   |   ❌    |
+    /// | `Mtu`                                 |    ❌     |   ✅    |  ❌   |   ❌    |
+    ///
+    /// ✅ = supported\
+    /// ⚠️ = only reported when the RSSI is read with [`rssi`][Self::rssi]\
+    /// ❌ = not reported
+    ///
+    /// On Android an error of kind [`NotSupported`][crate::error::ErrorKind::NotSupported] is returned.
+    #[inline]
+    pub async fn property_changes(&self) -> Result<impl Stream<Item = DeviceProperty> + Send + Unpin + '_> {
+        self.0.property_changes().await
+    }
+
+    /// Open an L2CAP connection-oriented channel (CoC) to this device.
+    ///
+    /// The channel is only opened if the link meets the requested `security`. Use
+    /// [`L2CapSecurity::NONE`] to open an unencrypted channel.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on Windows. On MacOS/iOS, only
+    /// [`L2CapSecurity::NONE`] is supported; the security of the channel is determined by the peripheral.
     #[cfg(feature = "l2cap")]
-    pub async fn open_l2cap_channel(&self, psm: u16, secure: bool) -> Result<L2CapChannel> {
-        self.0.open_l2cap_channel(psm, secure).await
+    pub async fn open_l2cap_channel(&self, psm: u16, security: L2CapSecurity) -> Result<L2CapChannel> {
+        self.0.open_l2cap_channel(psm, security).await
     }
+
+    /// Open an L2CAP connection-oriented channel (CoC) to the PSM published by a GATT service of this device.
+    ///
+    /// Discovers the service with `service_uuid` and reads its
+    /// [`L2CAPPSM_CHARACTERISTIC`][crate::btuuid::descriptors::L2CAPPSM_CHARACTERISTIC], a little-endian `u16`, which
+    /// is the convention used by CoreBluetooth peripherals publishing a channel. The channel is then opened as with
+    /// [`open_l2cap_channel`][Self::open_l2cap_channel].
+    ///
+    /// Returns an error of kind [`NotFound`][crate::error::ErrorKind::NotFound] if the device does not have the
+    /// service or the service does not have the PSM characteristic, and an error of kind
+    /// [`InvalidParameter`][crate::error::ErrorKind::InvalidParameter] if the value of the characteristic is not a
+    /// 16-bit PSM.
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_channel_for_service(
+        &self,
+        service_uuid: Uuid,
+        security: L2CapSecurity,
+    ) -> Result<L2CapChannel> {
+        let services = self.discover_services_with_uuid(service_uuid).await?;
+        let service = services
+            .first()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, format!("service {service_uuid} not found")))?;
+
+        let characteristics = service
+            .discover_characteristics_with_uuid(crate::btuuid::descriptors::L2CAPPSM_CHARACTERISTIC)
+            .await?;
+        let characteristic = characteristics.first().ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                None,
+                format!("service {service_uuid} does not publish an L2CAP PSM"),
+            )
+        })?;
+
+        let value = characteristic.read().await?;
+        let psm = <[u8; 2]>::try_from(value.as_slice())
+            .map(u16::from_le_bytes)
+            .map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidParameter,
+                    None,
+                    format!("invalid L2CAP PSM value of {} bytes", value.len()),
+                )
+            })?;
+
+        self.open_l2cap_channel(psm, security).await
+    }
+
+    /// Open an L2CAP connection-oriented channel (CoC) to this device in sequential packet mode.
+    ///
+    /// The returned channel preserves the boundaries of the SDUs sent by either side.
+    ///
+    /// # Platform specific
+    ///
+    /// Returns [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS, Windows and Android.
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_packet_channel(&self, psm: u16, security: L2CapSecurity) -> Result<L2CapPacketChannel> {
+        self.0
+            .open_l2cap_packet_channel(psm, security)
+            .await
+            .map(L2CapPacketChannel)
+    }
+}
+
+/// A change of a property of a [`Device`], reported by [`Device::property_changes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceProperty {
+    /// The name of the device, as returned by [`Device::name`]
+    Name(String),
+    /// The signal strength of the most recently received advertisement in dBm
+    Rssi(i16),
+    /// The advertised transmit power level in dBm
+    TxPower(i16),
+    /// Whether the device is paired with the host system
+    Paired(bool),
+    /// Whether the device is trusted by the host system
+    Trusted(bool),
+    /// Whether service discovery has completed after connecting
+    ServicesResolved(bool),
+    /// All manufacturer specific data entries of the most recently received advertisement
+    ManufacturerData(Vec<ManufacturerData>),
+    /// The service data of the most recently received advertisement
+    ServiceData(HashMap<Uuid, Vec<u8>>),
+    /// The ATT MTU negotiated for the connection
+    Mtu(usize),
 }
 
 /// A services changed notification
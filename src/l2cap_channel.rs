@@ -1,26 +1,250 @@
-use tokio::io::{AsyncRead, AsyncWrite};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
-use crate::sys;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{sys, Device, DeviceId, Result, SecurityLevel};
 
 /// A Bluetooth LE L2CAP Connection-oriented Channel (CoC)
+///
+/// The channel implements tokio's [`AsyncRead`] and [`AsyncWrite`]. With the `futures-io` feature enabled, it also
+/// implements the `futures-io` equivalents, which forward to the tokio implementations.
+///
+/// # Platform specifics
+///
+/// On Linux and MacOS/iOS, the channel is registered with the tokio reactor, so it must be polled from within a tokio
+/// runtime even when it is used through the `futures-io` traits.
 pub type L2CapChannel = sys::l2cap_channel::Channel;
 
 /// Trait for functions that all L2Cap Channels have
 pub trait L2CapChannelImpl: AsyncRead + AsyncWrite {
     /// Split the channel into a reader and write half
     fn split(self) -> (L2CapReader, L2CapWriter);
+
+    /// The PSM of the channel
+    fn psm(&self) -> Result<u16>;
+
+    /// The device on the other end of the channel
+    fn peer(&self) -> Result<DeviceId>;
+
+    /// The maximum size of an SDU which can be sent to the peer, as negotiated when the channel was opened
+    ///
+    /// Writes larger than this are segmented into several SDUs.
+    ///
+    /// Returns an error of kind [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    fn send_mtu(&self) -> Result<usize>;
+
+    /// The maximum size of an SDU which can be received from the peer, as negotiated when the channel was opened
+    ///
+    /// Returns an error of kind [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    fn recv_mtu(&self) -> Result<usize>;
+
+    /// The security of the link the channel is carried on
+    ///
+    /// Returns an error of kind [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    fn security(&self) -> Result<L2CapSecurity>;
+
+    /// The credit-based flow control mode of the channel
+    ///
+    /// The credits themselves are managed by the Bluetooth stack and are not exposed on any platform.
+    ///
+    /// Returns an error of kind [`NotSupported`][crate::error::ErrorKind::NotSupported] on MacOS/iOS and Android.
+    fn flow_control(&self) -> Result<L2CapFlowControl>;
 }
 
-/// Reader half of Bluetooth LE L2CAP Connection-oriented Channel (CoC)
-pub type L2CapReader = sys::l2cap_channel::Reader;
+/// The security of an L2CAP channel
+///
+/// Used both to request a minimum security when opening or listening for channels and to report the security
+/// negotiated for an open channel.
+///
+/// # Platform specifics
+///
+/// On Linux, the key size is reported for open channels but is not enforced when requesting security.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct L2CapSecurity {
+    /// The security level of the link
+    pub level: SecurityLevel,
+    /// The size of the encryption key in bytes, from 7 to 16
+    ///
+    /// `0` means the link is not encrypted or, when requesting security, that any key size is acceptable.
+    pub key_size: u8,
+}
 
-trait _L2CapReaderImpl: AsyncRead {}
+impl L2CapSecurity {
+    /// No encryption or authentication
+    pub const NONE: L2CapSecurity = L2CapSecurity {
+        level: SecurityLevel::Low,
+        key_size: 0,
+    };
+}
 
-impl _L2CapReaderImpl for L2CapReader {}
+impl Default for L2CapSecurity {
+    fn default() -> Self {
+        L2CapSecurity::NONE
+    }
+}
+
+impl From<SecurityLevel> for L2CapSecurity {
+    fn from(level: SecurityLevel) -> Self {
+        L2CapSecurity { level, key_size: 0 }
+    }
+}
+
+/// The credit-based flow control mode of an L2CAP channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum L2CapFlowControl {
+    /// LE credit-based flow control, introduced in Bluetooth 4.1
+    LeCredit,
+    /// Enhanced credit-based flow control, introduced in Bluetooth 5.2
+    EnhancedCredit,
+}
+
+/// Reader half of Bluetooth LE L2CAP Connection-oriented Channel (CoC)
+///
+/// Implements [`AsyncRead`], and `futures_io::AsyncRead` with the `futures-io` feature enabled.
+pub struct L2CapReader(pub(crate) sys::l2cap_channel::Reader);
+
+impl AsyncRead for L2CapReader {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
 
 /// Writer half of Bluetooth LE L2CAP Connection-oriented Channel (CoC)
-pub type L2CapWriter = sys::l2cap_channel::Writer;
+///
+/// Implements [`AsyncWrite`], and `futures_io::AsyncWrite` with the `futures-io` feature enabled.
+pub struct L2CapWriter(pub(crate) sys::l2cap_channel::Writer);
 
-trait _L2CapWriterImpl: AsyncWrite {}
+impl AsyncWrite for L2CapWriter {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
 
-impl _L2CapWriterImpl for L2CapWriter {}
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+/// Implements the `futures-io` traits for types implementing the tokio traits
+///
+/// This only adapts the traits; the underlying channels still require a tokio runtime on Linux and MacOS/iOS.
+#[cfg(feature = "futures-io")]
+mod futures_io_impls {
+    use std::io::Result;
+    use std::pin::Pin;
+    use std::task::{ready, Context, Poll};
+
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use super::{L2CapChannel, L2CapReader, L2CapWriter};
+
+    fn poll_read<T: AsyncRead + Unpin>(io: &mut T, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let mut buf = ReadBuf::new(buf);
+        ready!(Pin::new(io).poll_read(cx, &mut buf))?;
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+
+    macro_rules! impl_futures_io_read {
+        ($ty:ty) => {
+            impl futures_io::AsyncRead for $ty {
+                fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize>> {
+                    poll_read(&mut *self, cx, buf)
+                }
+            }
+        };
+    }
+
+    macro_rules! impl_futures_io_write {
+        ($ty:ty) => {
+            impl futures_io::AsyncWrite for $ty {
+                fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+                    AsyncWrite::poll_write(Pin::new(&mut *self), cx, buf)
+                }
+
+                fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+                    AsyncWrite::poll_flush(Pin::new(&mut *self), cx)
+                }
+
+                fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+                    AsyncWrite::poll_shutdown(Pin::new(&mut *self), cx)
+                }
+            }
+        };
+    }
+
+    impl_futures_io_read!(L2CapChannel);
+    impl_futures_io_write!(L2CapChannel);
+    impl_futures_io_read!(L2CapReader);
+    impl_futures_io_write!(L2CapWriter);
+}
+
+/// A listener for Bluetooth LE L2CAP Connection-oriented Channels (CoC) opened by remote devices
+///
+/// Created by [`Adapter::listen_l2cap`][crate::Adapter::listen_l2cap]. The listener stops accepting channels when it is
+/// dropped.
+#[derive(Debug)]
+pub struct L2CapListener(pub(crate) sys::l2cap_channel::Listener);
+
+impl L2CapListener {
+    /// The PSM this listener accepts channels on
+    ///
+    /// If the listener was created without a fixed PSM, this is the PSM assigned by the system.
+    #[inline]
+    pub fn psm(&self) -> u16 {
+        self.0.psm()
+    }
+
+    /// Waits for a remote device to open a channel, returning the channel along with the device which opened it
+    #[inline]
+    pub async fn accept(&self) -> Result<(L2CapChannel, Device)> {
+        self.0.accept().await
+    }
+}
+
+/// A Bluetooth LE L2CAP Connection-oriented Channel (CoC) in sequential packet mode
+///
+/// Unlike [`L2CapChannel`], which is a byte stream, a packet channel preserves message boundaries: each call to
+/// [`send`][Self::send] transmits exactly one SDU and each call to [`recv`][Self::recv] returns exactly one.
+#[derive(Debug)]
+pub struct L2CapPacketChannel(pub(crate) sys::l2cap_channel::PacketChannel);
+
+impl L2CapPacketChannel {
+    /// Sends `packet` as a single SDU
+    ///
+    /// Returns an error of kind [`InvalidParameter`][crate::error::ErrorKind::InvalidParameter] if `packet` is larger
+    /// than the [`send_mtu`][Self::send_mtu].
+    #[inline]
+    pub async fn send(&self, packet: &[u8]) -> Result<()> {
+        self.0.send(packet).await
+    }
+
+    /// Receives the next SDU
+    ///
+    /// Returns an error of kind [`NotConnected`][crate::error::ErrorKind::NotConnected] once the channel has been
+    /// closed.
+    #[inline]
+    pub async fn recv(&self) -> Result<Vec<u8>> {
+        self.0.recv().await
+    }
+
+    /// The maximum size of packets which can be sent on this channel, as negotiated with the peer
+    #[inline]
+    pub fn send_mtu(&self) -> Result<usize> {
+        self.0.send_mtu()
+    }
+
+    /// The maximum size of packets which can be received on this channel, as negotiated with the peer
+    #[inline]
+    pub fn recv_mtu(&self) -> Result<usize> {
+        self.0.recv_mtu()
+    }
+}
@@ -61,9 +61,9 @@
 //! # Asynchronous runtimes
 //!
 //! On non-linux platforms, Bluest should work with any asynchronous runtime. On linux the underlying `bluer` crate
-//! requires the Tokio runtime and Bluest makes use of Tokio's `block_in_place` API (which requires Tokio's
-//! multi-threaded runtime) to make a few methods synchronous. Linux-only asynchronous versions of those methods are
-//! also provided, which should be preferred in platform-specific code.
+//! requires the Tokio runtime, either single- or multi-threaded. The few synchronous methods return values cached by
+//! Bluest on linux. Asynchronous versions of those methods are also provided, which return up-to-date values and should
+//! be preferred in platform-specific code.
 //!
 //! # Platform specifics
 //!
@@ -79,21 +79,24 @@
 //!|----------------------------------------------------------|:---------:|:-------:|:-----:|
 //!| [`Adapter::connect_device`][Adapter::connect_device]                     | ✅ | ✨ | ✅ |
 //!| [`Adapter::disconnect_device`][Adapter::disconnect_device]               | ✅ | ✨ | ✅ |
-//!| [`Device::name`][Device::name]                                           | ✅ | ✅ | ⌛️ |
+//!| [`Device::name`][Device::name]                                           | ✅ | ✅ | ✅ |
+//!| [`Device::alias`][Device::alias]                                         | ❌ | ❌ | ✅ |
 //!| [`Device::is_paired`][Device::is_paired]                                 | ❌ | ✅ | ✅ |
 //!| [`Device::pair`][Device::pair]                                           | ✨ | ✅ | ✅ |
 //!| [`Device::pair_with_agent`][Device::pair_with_agent]                     | ✨ | ✅ | ✅ |
 //!| [`Device::unpair`][Device::unpair]                                       | ❌ | ✅ | ✅ |
-//!| [`Device::rssi`][Device::rssi]                                           | ✅ | ❌ | ❌ |
-//!| [`Service::uuid`][Service::uuid]                                         | ✅ | ✅ | ⌛️ |
+//!| [`Device::security_level`][Device::security_level]                       | ❌ | ✅ | ❌ |
+//!| [`Device::is_encrypted`][Device::is_encrypted]                           | ❌ | ✅ | ❌ |
+//!| [`Device::rssi`][Device::rssi]                                           | ✅ | ❌ | ✅ |
+//!| [`Device::refresh_rssi`][Device::refresh_rssi]                           | ✅ | ❌ | ✅ |
+//!| [`Service::uuid`][Service::uuid]                                         | ✅ | ✅ | ✅ |
 //!| [`Service::is_primary`][Service::is_primary]                             | ✅ | ❌ | ✅ |
-//!| [`Characteristic::uuid`][Characteristic::uuid]                           | ✅ | ✅ | ⌛️ |
-//!| [`Characteristic::max_write_len`][Characteristic::max_write_len]         | ✅ | ✅ | ⌛️ |
-//!| [`Descriptor::uuid`][Descriptor::uuid]                                   | ✅ | ✅ | ⌛️ |
+//!| [`Characteristic::uuid`][Characteristic::uuid]                           | ✅ | ✅ | ✅ |
+//!| [`Characteristic::max_write_len`][Characteristic::max_write_len]         | ✅ | ✅ | ✅ |
+//!| [`Descriptor::uuid`][Descriptor::uuid]                                   | ✅ | ✅ | ✅ |
 //!
 //! ✅ = supported
 //! ✨ = managed automatically by the OS, this method is a no-op
-//! ⌛️ = the underlying API is async so this method uses Tokio's `block_in_place` API internally
 //! ❌ = returns a [`NotSupported`][error::ErrorKind::NotSupported] error
 //!
 //! Also, the errors returned by APIs in a given situation may not be consistent from platform to platform. For example,
@@ -107,6 +110,14 @@
 //! The `serde` feature is available to enable serializing/deserializing device
 //! identifiers.
 //!
+//! The `mock` feature replaces the platform Bluetooth stack with an in-memory simulation for testing applications
+//! without Bluetooth hardware. See the documentation of the `mock` module for details.
+//!
+//! The `l2cap` feature enables L2CAP connection-oriented channels. Channels implement tokio's `AsyncRead` and
+//! `AsyncWrite` traits. The `futures-io` feature additionally implements the `futures-io` traits, for use with libraries
+//! built on them. The channels still depend on tokio: on Linux and MacOS/iOS they must be polled from within a tokio
+//! runtime.
+//!
 //! # Examples
 //!
 //! Examples demonstrating basic usage are available in the [examples folder].
@@ -114,22 +125,27 @@
 //! [examples folder]: https://github.com/alexmoon/bluest/tree/master/bluest/examples
 
 mod adapter;
+mod advertisement;
 pub mod btuuid;
 mod characteristic;
 mod descriptor;
 mod device;
 pub mod error;
+mod managed;
 pub mod pairing;
+mod scan;
 mod service;
 mod util;
 
-#[cfg(target_os = "android")]
+#[cfg(all(target_os = "android", not(feature = "mock")))]
 mod android;
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "mock")))]
 mod bluer;
-#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg(all(any(target_os = "macos", target_os = "ios"), not(feature = "mock")))]
 mod corebluetooth;
-#[cfg(target_os = "windows")]
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(all(target_os = "windows", not(feature = "mock")))]
 mod windows;
 
 use std::collections::HashMap;
@@ -140,27 +156,43 @@ pub use adapter::Adapter;
 pub use btuuid::BluetoothUuidExt;
 pub use characteristic::Characteristic;
 pub use descriptor::Descriptor;
-pub use device::{Device, ServicesChanged};
+pub use device::{Device, DeviceProperty, ServicesChanged};
 pub use error::Error;
 #[cfg(feature = "l2cap")]
-pub use l2cap_channel::{L2CapChannel, L2CapChannelImpl, L2CapReader, L2CapWriter};
+pub use l2cap_channel::{
+    L2CapChannel, L2CapChannelImpl, L2CapFlowControl, L2CapListener, L2CapPacketChannel, L2CapReader, L2CapSecurity,
+    L2CapWriter,
+};
+pub use managed::{ManagedDevice, ManagedNotification, ReconnectPolicy};
+pub use scan::{
+    DuplicatePolicy, LocalNameFilter, ManufacturerDataFilter, ScanFilter, ScanMode, ScanOptions, ScanPredicate,
+    ScanTransport, ServiceDataFilter,
+};
 pub use service::Service;
 pub use sys::DeviceId;
 #[cfg(not(target_os = "linux"))]
 pub use uuid::Uuid;
 
-#[cfg(target_os = "android")]
+#[cfg(all(target_os = "android", not(feature = "mock")))]
 use crate::android as sys;
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "mock")))]
 use crate::bluer as sys;
-#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg(all(any(target_os = "macos", target_os = "ios"), not(feature = "mock")))]
 use crate::corebluetooth as sys;
-#[cfg(target_os = "windows")]
+#[cfg(feature = "mock")]
+use crate::mock as sys;
+#[cfg(all(target_os = "windows", not(feature = "mock")))]
 use crate::windows as sys;
 
 #[cfg(all(
     feature = "l2cap",
-    any(target_os = "android", target_os = "linux", target_os = "macos", target_os = "ios")
+    any(
+        feature = "mock",
+        target_os = "android",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "ios"
+    )
 ))]
 mod l2cap_channel;
 
@@ -180,9 +212,109 @@ pub enum AdapterEvent {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ConnectionEvent {
     /// The device has disconnected from the host system
-    Disconnected,
+    Disconnected {
+        /// Why the connection was closed, if reported by the platform
+        reason: DisconnectReason,
+    },
     /// The device has connected to the host system
     Connected,
+    /// A connection to the device is being established
+    ///
+    /// Only reported on Android.
+    Connecting,
+    /// The connection to the device is being closed
+    ///
+    /// Only reported on Android.
+    Disconnecting,
+}
+
+/// Events generated by [`Adapter::device_events`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// A device became known to the adapter, e.g. because it was discovered by a scan
+    Added(crate::Device),
+    /// A device was removed from the adapter
+    Removed(DeviceId),
+    /// A device has connected to the host system
+    Connected(crate::Device),
+    /// A device has disconnected from the host system
+    Disconnected {
+        /// The device which disconnected
+        device: crate::Device,
+        /// Why the connection was closed, if reported by the platform
+        reason: DisconnectReason,
+    },
+    /// A device was paired with the host system
+    Paired(crate::Device),
+    /// A device was unpaired from the host system
+    Unpaired(crate::Device),
+    /// The name of a device changed
+    NameChanged {
+        /// The renamed device
+        device: crate::Device,
+        /// The new name of the device, as returned by [`Device::name`]
+        name: String,
+    },
+}
+
+/// The reason for a [`ConnectionEvent::Disconnected`] event
+///
+/// # Platform specifics
+///
+/// ## MacOS/iOS
+///
+/// The reason is derived from the error reported by CoreBluetooth. An HCI reason code is never available.
+///
+/// ## Linux/Windows/Android
+///
+/// The reason is not reported, so it is always [`Unknown`][DisconnectReason::Unknown].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DisconnectReason {
+    /// The platform did not report why the connection was closed
+    Unknown,
+    /// The connection was closed by the local host, e.g. by [`Adapter::disconnect_device`]
+    LocalHost,
+    /// The connection was closed by the remote device
+    RemoteDevice,
+    /// The connection supervision timeout expired, i.e. the link was lost without either side closing it. This
+    /// typically happens when the device moves out of range or loses power.
+    LinkLoss,
+    /// The connection was closed with an HCI reason code not covered by the other variants
+    ///
+    /// See the Bluetooth Core Specification, Vol 1, Part F for the meaning of the code.
+    Hci(u8),
+}
+
+impl DisconnectReason {
+    /// Interprets an HCI disconnection reason code as defined in the Bluetooth Core Specification, Vol 1, Part F.
+    pub fn from_hci_code(code: u8) -> Self {
+        match code {
+            // Connection Timeout
+            0x08 => DisconnectReason::LinkLoss,
+            // Remote User Terminated Connection, Remote Device Terminated Connection due to Low Resources, Remote
+            // Device Terminated Connection due to Power Off
+            0x13..=0x15 => DisconnectReason::RemoteDevice,
+            // Connection Terminated By Local Host
+            0x16 => DisconnectReason::LocalHost,
+            code => DisconnectReason::Hci(code),
+        }
+    }
+}
+
+/// The security level of a link, from weakest to strongest
+///
+/// See the Bluetooth Core Specification, Vol 3, Part C, §10.2 for the LE security modes and levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SecurityLevel {
+    /// No encryption and no authentication (LE security mode 1, level 1)
+    Low,
+    /// Encryption without authentication, e.g. after "JustWorks" pairing (LE security mode 1, level 2)
+    Medium,
+    /// Encryption with authentication, providing protection from man-in-the-middle attacks (LE security mode 1,
+    /// level 3)
+    High,
+    /// Authenticated LE Secure Connections pairing with a 128-bit encryption key (LE security mode 1, level 4)
+    Fips,
 }
 
 /// Represents a device discovered during a scan operation
@@ -197,19 +329,43 @@ pub struct AdvertisingDevice {
 }
 
 /// Data included in a Bluetooth advertisement or scan reponse.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct AdvertisementData {
     /// The (possibly shortened) local name of the device (CSS §A.1.2)
     pub local_name: Option<String>,
+    /// The shortened local name of the device, if it was advertised separately from the complete name (CSS §A.1.2)
+    pub shortened_local_name: Option<String>,
     /// Manufacturer specific data (CSS §A.1.4)
+    ///
+    /// If the advertisement contains more than one manufacturer specific data entry, this is the first of
+    /// [`all_manufacturer_data`][AdvertisementData::all_manufacturer_data].
     pub manufacturer_data: Option<ManufacturerData>,
+    /// All manufacturer specific data entries included in the advertisement (CSS §A.1.4)
+    pub all_manufacturer_data: Vec<ManufacturerData>,
     /// Advertised GATT service UUIDs (CSS §A.1.1)
     pub services: Vec<Uuid>,
+    /// Service solicitation UUIDs (CSS §A.1.10)
+    pub solicited_services: Vec<Uuid>,
     /// Service associated data (CSS §A.1.11)
     pub service_data: HashMap<Uuid, Vec<u8>>,
     /// Transmitted power level (CSS §A.1.5)
     pub tx_power_level: Option<i16>,
+    /// Advertising flags (CSS §A.1.3)
+    pub flags: Option<u8>,
+    /// External appearance of the device (CSS §A.1.12)
+    pub appearance: Option<u16>,
+    /// Advertised URI (CSS §A.1.18)
+    pub uri: Option<String>,
+    /// LE supported features bitmask (CSS §A.1.19)
+    pub le_supported_features: Option<Vec<u8>>,
+    /// AD structures which were not recognized, as `(ad_type, data)` pairs
+    pub unknown_data: Vec<(u8, Vec<u8>)>,
     /// Set to true for connectable advertising packets
+    ///
+    /// # Platform specific
+    ///
+    /// On Linux, BlueZ does not report the advertising packet type, so whether an advertisement is connectable is
+    /// unknown and this is always `true`.
     pub is_connectable: bool,
 }
 
@@ -0,0 +1,246 @@
+//! Automatic reconnection for [`ManagedDevice`].
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures_core::Stream;
+use futures_lite::{stream, StreamExt};
+use tracing::{debug, warn};
+
+use crate::error::ErrorKind;
+use crate::util::sleep;
+use crate::{Adapter, ConnectionEvent, Device, Error, Result, Uuid};
+
+/// How a [`ManagedDevice`] retries failed connection attempts
+///
+/// The delay between attempts starts at [`initial_delay`][Self::initial_delay] and doubles after each failed attempt,
+/// up to [`max_delay`][Self::max_delay].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReconnectPolicy {
+    /// Delay after the first failed connection attempt
+    pub initial_delay: Duration,
+    /// Maximum delay between connection attempts
+    pub max_delay: Duration,
+    /// Number of consecutive failed connection attempts after which to give up, or `None` to retry indefinitely
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            max_attempts: None,
+        }
+    }
+}
+
+/// An item of the stream returned by [`ManagedDevice::notifications`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ManagedNotification {
+    /// A value notified or indicated by the characteristic
+    Value(Vec<u8>),
+    /// The connection to the device was lost. Values sent by the device before the following
+    /// [`Reconnected`][Self::Reconnected] are missed.
+    Disconnected,
+    /// The device was reconnected and the subscription to the characteristic was re-established
+    Reconnected,
+}
+
+/// A [`Device`] which is automatically reconnected when its connection is lost
+///
+/// Connection attempts are retried according to a [`ReconnectPolicy`]. Streams returned by
+/// [`notifications`][Self::notifications] survive disconnections: after reconnecting, services are discovered again
+/// and the subscription is re-established, with [`ManagedNotification::Disconnected`] and
+/// [`ManagedNotification::Reconnected`] marking the gap in between.
+///
+/// Reconnection is driven by polling the notification streams, so no background task is required.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use bluest::{btuuid, Adapter, ManagedDevice, ManagedNotification, ReconnectPolicy};
+/// # use futures_lite::StreamExt;
+/// # async fn example(adapter: Adapter, device: bluest::Device) -> bluest::Result<()> {
+/// let device = ManagedDevice::new(adapter, device, ReconnectPolicy::default());
+/// let mut notifications = device.notifications(
+///     btuuid::services::HEART_RATE,
+///     btuuid::characteristics::HEART_RATE_MEASUREMENT,
+/// );
+/// while let Some(notification) = notifications.next().await {
+///     match notification? {
+///         ManagedNotification::Value(value) => println!("{value:?}"),
+///         ManagedNotification::Disconnected => println!("connection lost"),
+///         ManagedNotification::Reconnected => println!("reconnected"),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ManagedDevice {
+    adapter: Adapter,
+    device: Device,
+    policy: ReconnectPolicy,
+}
+
+enum SubscriptionEvent {
+    Notification(Result<Vec<u8>>),
+    NotificationsEnded,
+    Connection(ConnectionEvent),
+}
+
+struct Subscription<'a> {
+    events: Pin<Box<dyn Stream<Item = SubscriptionEvent> + Send + 'a>>,
+}
+
+enum State<'a> {
+    Connecting { reconnected: bool },
+    Subscribed(Subscription<'a>),
+    Done,
+}
+
+impl ManagedDevice {
+    /// Creates a managed wrapper for `device`, which is connected through `adapter` using the given `policy`.
+    ///
+    /// No connection is attempted until [`connect`][Self::connect] or [`notifications`][Self::notifications] is
+    /// called.
+    pub fn new(adapter: Adapter, device: Device, policy: ReconnectPolicy) -> Self {
+        ManagedDevice {
+            adapter,
+            device,
+            policy,
+        }
+    }
+
+    /// The managed device
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The policy used to retry failed connection attempts
+    pub fn policy(&self) -> &ReconnectPolicy {
+        &self.policy
+    }
+
+    /// Connects to the device if it is not already connected, retrying failed attempts according to the
+    /// [`ReconnectPolicy`].
+    ///
+    /// Returns the error of the last attempt if the policy's [`max_attempts`][ReconnectPolicy::max_attempts] is
+    /// exceeded.
+    pub async fn connect(&self) -> Result<()> {
+        let mut delay = self.policy.initial_delay;
+        let mut attempts = 0;
+        loop {
+            if self.device.is_connected().await {
+                return Ok(());
+            }
+
+            match self.adapter.connect_device(&self.device).await {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    attempts += 1;
+                    if self.policy.max_attempts.is_some_and(|max| attempts >= max) {
+                        return Err(err);
+                    }
+                    debug!(
+                        "Connection attempt {} failed, retrying in {:?}: {:?}",
+                        attempts, delay, err
+                    );
+                    sleep(delay).await;
+                    delay = (delay * 2).min(self.policy.max_delay);
+                }
+            }
+        }
+    }
+
+    /// Subscribes to notifications or indications of the characteristic `characteristic` of the service `service`.
+    ///
+    /// The device is connected if necessary. When the connection is lost, a [`ManagedNotification::Disconnected`] is
+    /// yielded and the device is reconnected. Once the characteristic has been discovered and subscribed to again, a
+    /// [`ManagedNotification::Reconnected`] is yielded and values are reported as before.
+    ///
+    /// The stream yields an error and ends if connecting fails according to the [`ReconnectPolicy`] or if the
+    /// characteristic cannot be found or subscribed to. Other errors reported by the subscription are passed on
+    /// without ending the stream.
+    pub fn notifications(
+        &self,
+        service: Uuid,
+        characteristic: Uuid,
+    ) -> impl Stream<Item = Result<ManagedNotification>> + Send + Unpin + '_ {
+        Box::pin(stream::unfold(
+            State::Connecting { reconnected: false },
+            move |mut state| async move {
+                loop {
+                    state = match state {
+                        State::Done => return None,
+                        State::Connecting { reconnected } => match self.subscribe(service, characteristic).await {
+                            Ok(subscription) if reconnected => {
+                                return Some((Ok(ManagedNotification::Reconnected), State::Subscribed(subscription)))
+                            }
+                            Ok(subscription) => State::Subscribed(subscription),
+                            Err(err) => return Some((Err(err), State::Done)),
+                        },
+                        State::Subscribed(mut subscription) => match subscription.events.next().await {
+                            Some(SubscriptionEvent::Notification(Ok(value))) => {
+                                return Some((Ok(ManagedNotification::Value(value)), State::Subscribed(subscription)))
+                            }
+                            Some(SubscriptionEvent::Notification(Err(err)))
+                                if err.kind() != ErrorKind::NotConnected =>
+                            {
+                                return Some((Err(err), State::Subscribed(subscription)))
+                            }
+                            Some(SubscriptionEvent::Connection(
+                                ConnectionEvent::Connected
+                                | ConnectionEvent::Connecting
+                                | ConnectionEvent::Disconnecting,
+                            )) => State::Subscribed(subscription),
+                            _ => {
+                                warn!("Lost connection to {:?}, reconnecting", self.device.id());
+                                return Some((
+                                    Ok(ManagedNotification::Disconnected),
+                                    State::Connecting { reconnected: true },
+                                ));
+                            }
+                        },
+                    }
+                }
+            },
+        ))
+    }
+
+    async fn subscribe(&self, service: Uuid, characteristic: Uuid) -> Result<Subscription<'_>> {
+        self.connect().await?;
+
+        let not_found =
+            |what: &str, uuid: Uuid| Error::new(ErrorKind::NotFound, None, format!("{what} {uuid} not found"));
+        let service = self
+            .device
+            .discover_services_with_uuid(service)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| not_found("service", service))?;
+        let characteristic = service
+            .discover_characteristics_with_uuid(characteristic)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| not_found("characteristic", characteristic))?;
+
+        let notifications = characteristic
+            .notify_owned()
+            .await?
+            .map(SubscriptionEvent::Notification)
+            .chain(stream::once(SubscriptionEvent::NotificationsEnded));
+        let connection_events = self
+            .adapter
+            .device_connection_events(&self.device)
+            .await?
+            .map(SubscriptionEvent::Connection);
+
+        Ok(Subscription {
+            events: Box::pin(notifications.or(connection_events)),
+        })
+    }
+}
@@ -0,0 +1,253 @@
+//! In-memory mock backend.
+//!
+//! When the `mock` feature is enabled, the platform Bluetooth stack is replaced by an in-process simulation. Every
+//! [`Adapter`] returned by [`Adapter::default()`] is an independent, empty virtual adapter, which is also returned by
+//! [`Adapter::all()`] for as long as it is in use. Virtual peripherals can be registered with [`add_peripheral`] and
+//! are then discovered, connected and accessed through the regular Bluest APIs.
+//! The remaining functions in this module play the role of the remote peripheral: updating advertisements, changing
+//! attribute values, sending notifications and dropping the connection.
+//!
+//! ```rust,no_run
+//!# use bluest::mock::{self, MockCharacteristic, MockPeripheral, MockService};
+//!# use bluest::{btuuid, Adapter, AdvertisementData, CharacteristicProperties};
+//!# #[tokio::main]
+//!# async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!let adapter = Adapter::default().await.ok_or("Bluetooth adapter not found")?;
+//!
+//!let mut properties = CharacteristicProperties::default();
+//!properties.read = true;
+//!properties.notify = true;
+//!
+//!let device = mock::add_peripheral(
+//!    &adapter,
+//!    MockPeripheral {
+//!        name: Some("Sensor".to_string()),
+//!        adv_data: AdvertisementData {
+//!            services: vec![btuuid::services::BATTERY],
+//!            is_connectable: true,
+//!            ..Default::default()
+//!        },
+//!        services: vec![MockService {
+//!            uuid: btuuid::services::BATTERY,
+//!            is_primary: true,
+//!            characteristics: vec![MockCharacteristic {
+//!                uuid: btuuid::characteristics::BATTERY_LEVEL,
+//!                properties,
+//!                value: vec![100],
+//!                ..Default::default()
+//!            }],
+//!            ..Default::default()
+//!        }],
+//!        ..Default::default()
+//!    },
+//!);
+//!
+//!adapter.connect_device(&device).await?;
+//!let service = &device.discover_services_with_uuid(btuuid::services::BATTERY).await?[0];
+//!let characteristic = &service.characteristics().await?[0];
+//!assert_eq!(characteristic.read().await?, vec![100]);
+//!
+//!mock::notify(characteristic, &[99]);
+//!#
+//!#    Ok(())
+//!# }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use futures_channel::mpsc::UnboundedSender;
+
+use crate::{
+    Adapter, AdvertisementData, Characteristic, CharacteristicProperties, Descriptor, Device, DisconnectReason, Uuid,
+};
+
+pub(crate) mod adapter;
+pub(crate) mod characteristic;
+pub(crate) mod descriptor;
+pub(crate) mod device;
+pub(crate) mod service;
+
+#[cfg(feature = "l2cap")]
+pub(crate) mod l2cap_channel;
+
+/// A platform-specific device identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceId(u64);
+
+impl std::fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "mock-{:08x}", self.0)
+    }
+}
+
+impl DeviceId {
+    fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        DeviceId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Description of a virtual peripheral registered with [`add_peripheral`]
+#[derive(Debug, Clone, Default)]
+pub struct MockPeripheral {
+    /// The name returned by [`Device::name`]
+    pub name: Option<String>,
+    /// The advertisement data reported when the peripheral is found by a scan
+    pub adv_data: AdvertisementData,
+    /// The signal strength reported with advertisements and by [`Device::rssi`]
+    pub rssi: Option<i16>,
+    /// Whether the peripheral is already paired with the adapter
+    pub paired: bool,
+    /// The GATT services hosted by the peripheral
+    pub services: Vec<MockService>,
+}
+
+/// Description of a GATT service hosted by a [`MockPeripheral`]
+#[derive(Debug, Clone, Default)]
+pub struct MockService {
+    /// The [`Uuid`] identifying the type of the service
+    pub uuid: Uuid,
+    /// Whether this is a primary service
+    pub is_primary: bool,
+    /// The characteristics of the service
+    pub characteristics: Vec<MockCharacteristic>,
+    /// The services included by this service
+    pub included_services: Vec<MockService>,
+}
+
+/// Description of a GATT characteristic hosted by a [`MockPeripheral`]
+#[derive(Debug, Clone, Default)]
+pub struct MockCharacteristic {
+    /// The [`Uuid`] identifying the type of the characteristic
+    pub uuid: Uuid,
+    /// The operations permitted on the characteristic
+    pub properties: CharacteristicProperties,
+    /// The initial value of the characteristic
+    pub value: Vec<u8>,
+    /// The descriptors of the characteristic
+    pub descriptors: Vec<MockDescriptor>,
+}
+
+/// Description of a GATT descriptor hosted by a [`MockPeripheral`]
+#[derive(Debug, Clone, Default)]
+pub struct MockDescriptor {
+    /// The [`Uuid`] identifying the type of the descriptor
+    pub uuid: Uuid,
+    /// The initial value of the descriptor
+    pub value: Vec<u8>,
+}
+
+/// Registers a virtual peripheral with `adapter`.
+///
+/// The peripheral is reported by any running or future scans until it is removed with [`remove_peripheral`].
+pub fn add_peripheral(adapter: &Adapter, peripheral: MockPeripheral) -> Device {
+    adapter.0.add_peripheral(peripheral)
+}
+
+/// Removes a virtual peripheral from its adapter, disconnecting it first if necessary.
+pub fn remove_peripheral(device: &Device) {
+    device.0.disconnect(DisconnectReason::RemoteDevice);
+    if let Some(adapter) = device.0.adapter() {
+        adapter.remove_device(&device.0);
+    }
+}
+
+/// Replaces the advertisement data of a virtual peripheral and reports it to all running scans.
+pub fn advertise(device: &Device, adv_data: AdvertisementData, rssi: Option<i16>) {
+    device.0.set_adv_data(adv_data, rssi);
+    if let Some(adapter) = device.0.adapter() {
+        adapter.advertise(&device.0);
+    }
+}
+
+/// Replaces the GATT services of a virtual peripheral.
+///
+/// A services changed indication invalidating all previous services is sent if the peripheral is connected.
+pub fn set_services(device: &Device, services: Vec<MockService>) {
+    device.0.set_services(services);
+}
+
+/// Replaces the name of a virtual peripheral.
+///
+/// When `name` is `None`, [`Device::name`] falls back to the advertised local name.
+pub fn set_name(device: &Device, name: Option<String>) {
+    device.0.set_name(name);
+}
+
+/// Simulates the peripheral dropping its connection to the adapter.
+///
+/// The disconnection is reported with [`DisconnectReason::RemoteDevice`]. Use [`disconnect_with_reason`] to simulate
+/// other causes, such as link loss.
+pub fn disconnect(device: &Device) {
+    disconnect_with_reason(device, DisconnectReason::RemoteDevice);
+}
+
+/// Simulates the connection to the peripheral being closed for the given `reason`.
+pub fn disconnect_with_reason(device: &Device, reason: DisconnectReason) {
+    device.0.disconnect(reason);
+}
+
+/// Powers the virtual adapter on or off.
+///
+/// Powering off the adapter disconnects all peripherals and ends all running scans.
+pub fn set_powered(adapter: &Adapter, powered: bool) {
+    adapter.0.set_powered_state(powered);
+}
+
+/// Changes the value of a characteristic without notifying subscribers.
+pub fn set_value(characteristic: &Characteristic, value: &[u8]) {
+    characteristic.0.set_value(value);
+}
+
+/// Changes the value of a characteristic and sends a notification to all subscribers.
+pub fn notify(characteristic: &Characteristic, value: &[u8]) {
+    characteristic.0.set_value(value);
+    characteristic.0.send_notification(value);
+}
+
+/// Changes the value of a descriptor.
+pub fn set_descriptor_value(descriptor: &Descriptor, value: &[u8]) {
+    descriptor.0.set_value(value);
+}
+
+/// Accepts L2CAP channels opened to `psm` on the virtual peripheral `device`.
+///
+/// Each channel opened with [`Device::open_l2cap_channel`] is yielded as the peripheral's end of the connection.
+/// Registering a new listener for the same `psm` replaces the previous one.
+#[cfg(feature = "l2cap")]
+pub fn listen_l2cap(device: &Device, psm: u16) -> impl futures_core::Stream<Item = crate::L2CapChannel> + Send + Unpin {
+    device.0.listen_l2cap(psm)
+}
+
+/// Accepts L2CAP channels in sequential packet mode opened to `psm` on the virtual peripheral `device`.
+///
+/// Each channel opened with [`Device::open_l2cap_packet_channel`] is yielded as the peripheral's end of the connection.
+/// Registering a new listener for the same `psm` replaces the previous one.
+#[cfg(feature = "l2cap")]
+pub fn listen_l2cap_packets(
+    device: &Device,
+    psm: u16,
+) -> impl futures_core::Stream<Item = crate::L2CapPacketChannel> + Send + Unpin {
+    use futures_lite::StreamExt;
+
+    device.0.listen_l2cap_packets(psm).map(crate::L2CapPacketChannel)
+}
+
+/// Opens an L2CAP channel from the virtual peripheral `device` to a listener created with [`Adapter::listen_l2cap`].
+///
+/// Returns the peripheral's end of the channel. The other end is yielded by the listener's
+/// [`accept`][crate::L2CapListener::accept]. The peripheral must be connected.
+#[cfg(feature = "l2cap")]
+pub fn open_l2cap(device: &Device, psm: u16) -> crate::Result<crate::L2CapChannel> {
+    device.0.open_l2cap_to_adapter(psm)
+}
+
+/// Sends `value` to every open receiver in `senders`, dropping the senders whose receiver has been closed.
+fn broadcast<T: Clone>(senders: &Mutex<Vec<UnboundedSender<T>>>, value: T) {
+    senders
+        .lock()
+        .unwrap()
+        .retain(|sender| sender.unbounded_send(value.clone()).is_ok());
+}
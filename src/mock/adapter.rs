@@ -0,0 +1,470 @@
+#[cfg(feature = "l2cap")]
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use futures_channel::mpsc::{self, UnboundedSender};
+use futures_core::Stream;
+use futures_lite::{stream, StreamExt};
+
+use super::device::{DeviceImpl, DeviceState};
+use super::{broadcast, MockPeripheral};
+use crate::error::ErrorKind;
+use crate::{
+    AdapterEvent, AdvertisingDevice, ConnectionEvent, Device, DeviceEvent, DeviceId, DisconnectReason, Error, Result,
+    ScanFilter, ScanOptions, Uuid,
+};
+
+/// The system's Bluetooth adapter interface.
+///
+/// The default adapter for the system may be accessed with the [`Adapter::default()`] method.
+#[derive(Debug, Clone)]
+pub struct AdapterImpl {
+    inner: Arc<AdapterState>,
+}
+
+/// All virtual adapters which have been created and not yet dropped, in order of creation
+static ADAPTERS: Mutex<Vec<Weak<AdapterState>>> = Mutex::new(Vec::new());
+
+#[derive(Debug)]
+pub(super) struct AdapterState {
+    name: String,
+    address: [u8; 6],
+    alias: Mutex<String>,
+    powered: AtomicBool,
+    discoverable: AtomicBool,
+    pairable: AtomicBool,
+    devices: Mutex<Vec<Arc<DeviceState>>>,
+    events: Mutex<Vec<UnboundedSender<AdapterEvent>>>,
+    device_events: Mutex<Vec<UnboundedSender<DeviceEvent>>>,
+    scans: Mutex<Vec<UnboundedSender<AdvertisingDevice>>>,
+    #[cfg(feature = "l2cap")]
+    l2cap_listeners: Mutex<HashMap<u16, UnboundedSender<(super::l2cap_channel::Channel, Device)>>>,
+}
+
+impl PartialEq for AdapterImpl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for AdapterImpl {}
+
+impl std::hash::Hash for AdapterImpl {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(Arc::as_ptr(&self.inner), state);
+    }
+}
+
+impl AdapterImpl {
+    /// Creates a new, empty virtual adapter
+    ///
+    /// Adapters are named `hci0`, `hci1`, ... in order of creation.
+    pub async fn default() -> Option<Self> {
+        static NEXT_INDEX: AtomicU16 = AtomicU16::new(0);
+        let index = NEXT_INDEX.fetch_add(1, Ordering::Relaxed);
+        let [hi, lo] = index.to_be_bytes();
+
+        let inner = Arc::new(AdapterState {
+            name: format!("hci{index}"),
+            // A locally administered address
+            address: [0x02, 0x00, 0x00, 0x00, hi, lo],
+            alias: Mutex::new(format!("Mock adapter {index}")),
+            powered: AtomicBool::new(true),
+            discoverable: AtomicBool::new(false),
+            pairable: AtomicBool::new(true),
+            devices: Default::default(),
+            events: Default::default(),
+            device_events: Default::default(),
+            scans: Default::default(),
+            #[cfg(feature = "l2cap")]
+            l2cap_listeners: Default::default(),
+        });
+
+        let mut adapters = ADAPTERS.lock().unwrap();
+        adapters.retain(|x| x.strong_count() > 0);
+        adapters.push(Arc::downgrade(&inner));
+        Some(AdapterImpl { inner })
+    }
+
+    /// Returns all virtual adapters which are still in use
+    pub async fn all() -> Result<Vec<Self>> {
+        Ok(ADAPTERS
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|inner| AdapterImpl { inner })
+            .collect())
+    }
+
+    /// Returns the virtual adapter with the given name
+    pub async fn by_name(name: &str) -> Result<Self> {
+        Self::all()
+            .await?
+            .into_iter()
+            .find(|x| x.inner.name == name)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, format!("no adapter named {name}")))
+    }
+
+    /// The system name of this adapter, which uniquely identifies it
+    pub fn name(&self) -> Result<String> {
+        Ok(self.inner.name.clone())
+    }
+
+    /// The Bluetooth address of this adapter, most significant byte first
+    pub async fn address(&self) -> Result<[u8; 6]> {
+        Ok(self.inner.address)
+    }
+
+    /// The human readable name of this adapter, which is shown to remote devices
+    pub async fn alias(&self) -> Result<String> {
+        Ok(self.inner.alias.lock().unwrap().clone())
+    }
+
+    /// Whether this adapter is powered on
+    pub async fn is_powered(&self) -> Result<bool> {
+        Ok(self.inner.is_powered())
+    }
+
+    /// Whether this adapter is discoverable by remote devices
+    pub async fn is_discoverable(&self) -> Result<bool> {
+        Ok(self.inner.discoverable.load(Ordering::Acquire))
+    }
+
+    /// Whether this adapter accepts pairing requests from remote devices
+    pub async fn is_pairable(&self) -> Result<bool> {
+        Ok(self.inner.pairable.load(Ordering::Acquire))
+    }
+
+    /// Powers this adapter on or off
+    pub async fn set_powered(&self, powered: bool) -> Result<()> {
+        self.set_powered_state(powered);
+        Ok(())
+    }
+
+    /// Makes this adapter discoverable by remote devices, or stops it from being discoverable
+    pub async fn set_discoverable(&self, discoverable: bool) -> Result<()> {
+        self.inner.discoverable.store(discoverable, Ordering::Release);
+        Ok(())
+    }
+
+    /// Sets how long this adapter stays discoverable after `set_discoverable` is called
+    ///
+    /// The mock backend never turns discoverability off automatically, so the timeout is ignored.
+    pub async fn set_discoverable_timeout(&self, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    /// Allows or disallows pairing requests from remote devices
+    pub async fn set_pairable(&self, pairable: bool) -> Result<()> {
+        self.inner.pairable.store(pairable, Ordering::Release);
+        Ok(())
+    }
+
+    /// Sets how long this adapter stays pairable after `set_pairable` is called
+    ///
+    /// The mock backend never turns pairing off automatically, so the timeout is ignored.
+    pub async fn set_pairable_timeout(&self, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
+    pub async fn events(&self) -> Result<impl Stream<Item = Result<AdapterEvent>> + Send + Unpin + '_> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.inner.events.lock().unwrap().push(sender);
+        Ok(receiver.map(Ok))
+    }
+
+    /// Asynchronously blocks until the adapter is available
+    pub async fn wait_available(&self) -> Result<()> {
+        let events = self.events().await?;
+        if !self.inner.is_powered() {
+            events
+                .skip_while(|x| x.is_ok() && !matches!(x, Ok(AdapterEvent::Available)))
+                .next()
+                .await
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::Internal,
+                        None,
+                        "adapter event stream closed unexpectedly".to_string(),
+                    )
+                })??;
+        }
+        Ok(())
+    }
+
+    /// Attempts to create the device identified by `id`
+    pub async fn open_device(&self, id: &DeviceId) -> Result<Device> {
+        self.inner
+            .devices
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|x| x.id == *id)
+            .map(|x| Device(DeviceImpl::new(x.clone())))
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, "opening device".to_string()))
+    }
+
+    /// Finds all connected Bluetooth LE devices
+    pub async fn connected_devices(&self) -> Result<Vec<Device>> {
+        Ok(self.devices().into_iter().filter(|x| x.0.is_connected_now()).collect())
+    }
+
+    /// Finds all devices which are paired with the adapter
+    pub async fn paired_devices(&self) -> Result<Vec<Device>> {
+        Ok(self.devices().into_iter().filter(|x| x.0.is_paired_now()).collect())
+    }
+
+    /// Finds all peripherals registered with the adapter
+    pub async fn known_devices(&self) -> Result<Vec<Device>> {
+        Ok(self.devices())
+    }
+
+    /// Finds all connected devices providing any service in `services`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `services` is empty.
+    pub async fn connected_devices_with_services(&self, services: &[Uuid]) -> Result<Vec<Device>> {
+        assert!(!services.is_empty());
+
+        Ok(self
+            .connected_devices()
+            .await?
+            .into_iter()
+            .filter(|x| x.0.has_any_service(services))
+            .collect())
+    }
+
+    /// Starts scanning for Bluetooth advertising packets.
+    ///
+    /// Every registered peripheral which is not connected is reported once when the scan starts. Afterwards an
+    /// advertisement is reported each time [`advertise`][crate::mock::advertise] is called.
+    pub async fn scan<'a>(
+        &'a self,
+        services: &'a [Uuid],
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
+        self.scan_with_filter(ScanFilter::with_services(services)).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter`.
+    pub async fn scan_with_filter(
+        &self,
+        filter: ScanFilter,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        self.scan_with_options(filter, ScanOptions::default()).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter` using the given `options`.
+    ///
+    /// The scan mode and transport have no effect on the virtual adapter.
+    pub async fn scan_with_options(
+        &self,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        options.check_mode(true)?;
+
+        if !self.inner.is_powered() {
+            return Err(ErrorKind::AdapterUnavailable.into());
+        }
+
+        let (sender, receiver) = mpsc::unbounded();
+        for device in self.devices() {
+            if !device.0.is_connected_now() {
+                let _ = sender.unbounded_send(device.0.advertisement());
+            }
+        }
+        self.inner.scans.lock().unwrap().push(sender);
+
+        Ok(receiver
+            .filter(move |x: &AdvertisingDevice| filter.matches(x) && options.matches(x))
+            .filter(options.duplicate_filter()))
+    }
+
+    /// Finds Bluetooth devices providing any service in `services`.
+    ///
+    /// Returns a stream of [`Device`] structs with matching connected devices returned first. If the stream is not
+    /// dropped before all matching connected devices are consumed then scanning will begin for devices advertising any
+    /// of the `services`. Scanning will continue until the stream is dropped.
+    pub async fn discover_devices<'a>(
+        &'a self,
+        services: &'a [Uuid],
+    ) -> Result<impl Stream<Item = Result<Device>> + Send + Unpin + 'a> {
+        let connected = if services.is_empty() {
+            self.connected_devices().await?
+        } else {
+            self.connected_devices_with_services(services).await?
+        };
+        let connected = stream::iter(connected).map(Ok);
+
+        // try_unfold is used to ensure we do not start scanning until the connected devices have been consumed
+        let advertising = Box::pin(stream::try_unfold(None, |state| async {
+            let mut stream = match state {
+                Some(stream) => stream,
+                None => self.scan(services).await?,
+            };
+            Ok(stream.next().await.map(|x| (x.device, Some(stream))))
+        }));
+
+        Ok(connected.chain(advertising))
+    }
+
+    /// Connects to the [`Device`]
+    pub async fn connect_device(&self, device: &Device) -> Result<()> {
+        if !self.inner.is_powered() {
+            return Err(ErrorKind::AdapterUnavailable.into());
+        }
+
+        if !self.inner.contains(&device.0) {
+            return Err(Error::new(
+                ErrorKind::ConnectionFailed,
+                None,
+                "the peripheral is not registered with this adapter".to_string(),
+            ));
+        }
+
+        device.0.connect();
+        Ok(())
+    }
+
+    /// Disconnects from the [`Device`]
+    pub async fn disconnect_device(&self, device: &Device) -> Result<()> {
+        if !self.inner.is_powered() {
+            return Err(ErrorKind::AdapterUnavailable.into());
+        }
+
+        device.0.disconnect(DisconnectReason::LocalHost);
+        Ok(())
+    }
+
+    /// Monitors a device for connection/disconnection events.
+    pub async fn device_connection_events<'a>(
+        &'a self,
+        device: &'a Device,
+    ) -> Result<impl Stream<Item = ConnectionEvent> + Send + Unpin + 'a> {
+        Ok(device.0.connection_events())
+    }
+
+    /// Monitors all devices known to the adapter for changes.
+    pub async fn device_events(&self) -> Result<impl Stream<Item = Result<DeviceEvent>> + Send + Unpin + '_> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.inner.device_events.lock().unwrap().push(sender);
+        Ok(receiver.map(Ok))
+    }
+
+    /// Listen for L2CAP connection-oriented channels opened by virtual peripherals with
+    /// [`open_l2cap`][crate::mock::open_l2cap].
+    #[cfg(feature = "l2cap")]
+    pub async fn listen_l2cap(
+        &self,
+        psm: Option<u16>,
+        security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::Listener> {
+        if !self.inner.is_powered() {
+            return Err(ErrorKind::AdapterUnavailable.into());
+        }
+
+        let mut listeners = self.inner.l2cap_listeners.lock().unwrap();
+        listeners.retain(|_, x| !x.is_closed());
+        let psm = match psm {
+            Some(psm) if listeners.contains_key(&psm) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidParameter,
+                    None,
+                    format!("PSM {psm} is already in use"),
+                ))
+            }
+            Some(psm) => psm,
+            // The range of LE PSMs which are assigned dynamically
+            None => (0x0080..=0x00ff)
+                .find(|x| !listeners.contains_key(x))
+                .ok_or_else(|| Error::new(ErrorKind::Other, None, "no dynamic PSM available".to_string()))?,
+        };
+
+        let (sender, receiver) = mpsc::unbounded();
+        listeners.insert(psm, sender);
+        Ok(super::l2cap_channel::Listener::new(psm, security, receiver))
+    }
+
+    pub(super) fn add_peripheral(&self, peripheral: MockPeripheral) -> Device {
+        let state = DeviceState::new(Arc::downgrade(&self.inner), peripheral);
+        self.inner.devices.lock().unwrap().push(state.clone());
+        let device = DeviceImpl::new(state);
+        self.inner.device_event(DeviceEvent::Added(Device(device.clone())));
+        self.inner.advertise(&device);
+        Device(device)
+    }
+
+    pub(super) fn set_powered_state(&self, powered: bool) {
+        if self.inner.powered.swap(powered, Ordering::AcqRel) == powered {
+            return;
+        }
+
+        if powered {
+            broadcast(&self.inner.events, AdapterEvent::Available);
+        } else {
+            for device in self.devices() {
+                device.0.disconnect(DisconnectReason::LocalHost);
+            }
+            // Dropping the senders ends all running scans
+            self.inner.scans.lock().unwrap().clear();
+            broadcast(&self.inner.events, AdapterEvent::Unavailable);
+        }
+    }
+
+    fn devices(&self) -> Vec<Device> {
+        self.inner
+            .devices
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|x| Device(DeviceImpl::new(x.clone())))
+            .collect()
+    }
+}
+
+impl AdapterState {
+    pub(super) fn is_powered(&self) -> bool {
+        self.powered.load(Ordering::Acquire)
+    }
+
+    pub(super) fn advertise(&self, device: &DeviceImpl) {
+        if self.is_powered() && self.contains(device) && !device.is_connected_now() {
+            broadcast(&self.scans, device.advertisement());
+        }
+    }
+
+    pub(super) fn remove_device(&self, device: &DeviceImpl) {
+        let removed = {
+            let mut devices = self.devices.lock().unwrap();
+            let len = devices.len();
+            devices.retain(|x| !device.is(x));
+            devices.len() != len
+        };
+        if removed {
+            self.device_event(DeviceEvent::Removed(device.id()));
+        }
+    }
+
+    pub(super) fn device_event(&self, event: DeviceEvent) {
+        broadcast(&self.device_events, event);
+    }
+
+    /// Opens a channel from `device` to the listener on `psm`, returning the device's end of the channel
+    ///
+    /// The security of the channel is set by the listener when it is accepted.
+    #[cfg(feature = "l2cap")]
+    pub(super) fn open_l2cap(&self, psm: u16, device: Device) -> Result<super::l2cap_channel::Channel> {
+        let (local, remote) = super::l2cap_channel::Channel::pair(psm, device.id(), crate::L2CapSecurity::NONE);
+        super::l2cap_channel::connect(&self.l2cap_listeners, psm, (local, device))?;
+        Ok(remote)
+    }
+
+    fn contains(&self, device: &DeviceImpl) -> bool {
+        self.devices.lock().unwrap().iter().any(|x| device.is(x))
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use futures_channel::mpsc::{self, UnboundedSender};
+use futures_core::Stream;
+use futures_lite::StreamExt;
+
+use super::descriptor::DescriptorState;
+use super::device::DeviceState;
+use super::{broadcast, MockCharacteristic};
+use crate::error::{AttError, ErrorKind};
+use crate::{Characteristic, CharacteristicProperties, Descriptor, Result, Uuid};
+
+/// The ATT MTU of all mock connections
+const MTU: usize = 23;
+
+/// A Bluetooth GATT characteristic
+#[derive(Clone)]
+pub struct CharacteristicImpl {
+    inner: Arc<CharacteristicState>,
+}
+
+pub(super) struct CharacteristicState {
+    device: Weak<DeviceState>,
+    uuid: Uuid,
+    properties: CharacteristicProperties,
+    value: Mutex<Vec<u8>>,
+    subscribers: Mutex<Vec<UnboundedSender<Vec<u8>>>>,
+    descriptors: Vec<Arc<DescriptorState>>,
+}
+
+impl std::fmt::Debug for CharacteristicImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Characteristic")
+            .field("uuid", &self.inner.uuid)
+            .field("properties", &self.inner.properties)
+            .finish()
+    }
+}
+
+impl PartialEq for CharacteristicImpl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for CharacteristicImpl {}
+
+impl std::hash::Hash for CharacteristicImpl {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(Arc::as_ptr(&self.inner), state);
+    }
+}
+
+impl Characteristic {
+    pub(super) fn new(inner: Arc<CharacteristicState>) -> Characteristic {
+        Characteristic(CharacteristicImpl { inner })
+    }
+}
+
+impl CharacteristicState {
+    pub(super) fn new(device: Weak<DeviceState>, characteristic: MockCharacteristic) -> Arc<Self> {
+        Arc::new(CharacteristicState {
+            uuid: characteristic.uuid,
+            properties: characteristic.properties,
+            value: Mutex::new(characteristic.value),
+            subscribers: Default::default(),
+            descriptors: characteristic
+                .descriptors
+                .into_iter()
+                .map(|x| DescriptorState::new(device.clone(), x))
+                .collect(),
+            device,
+        })
+    }
+
+    /// Ends all notification streams of this characteristic
+    pub(super) fn unsubscribe_all(&self) {
+        self.subscribers.lock().unwrap().clear();
+    }
+}
+
+impl CharacteristicImpl {
+    /// The [`Uuid`] identifying the type of this GATT characteristic
+    pub fn uuid(&self) -> Uuid {
+        self.inner.uuid
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT characteristic
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        Ok(self.uuid())
+    }
+
+    /// The properties of this this GATT characteristic.
+    ///
+    /// Characteristic properties indicate which operations (e.g. read, write, notify, etc) may be performed on this
+    /// characteristic.
+    pub async fn properties(&self) -> Result<CharacteristicProperties> {
+        Ok(self.inner.properties)
+    }
+
+    /// The cached value of this characteristic
+    ///
+    /// If the value has not yet been read, this method may either return an error or perform a read of the value.
+    pub async fn value(&self) -> Result<Vec<u8>> {
+        Ok(self.inner.value.lock().unwrap().clone())
+    }
+
+    /// Read the value of this characteristic from the device
+    pub async fn read(&self) -> Result<Vec<u8>> {
+        DeviceState::check_connected(&self.inner.device)?;
+        if !self.inner.properties.read {
+            return Err(ErrorKind::Protocol(AttError::READ_NOT_PERMITTED).into());
+        }
+        self.value().await
+    }
+
+    /// Write the value of this descriptor on the device to `value` and request the device return a response indicating
+    /// a successful write.
+    pub async fn write(&self, value: &[u8]) -> Result<()> {
+        self.write_kind(value, self.inner.properties.write)
+    }
+
+    /// Write the value of this descriptor on the device to `value` without requesting a response.
+    pub async fn write_without_response(&self, value: &[u8]) -> Result<()> {
+        self.write_kind(value, self.inner.properties.write_without_response)
+    }
+
+    fn write_kind(&self, value: &[u8], permitted: bool) -> Result<()> {
+        DeviceState::check_connected(&self.inner.device)?;
+        if !permitted {
+            return Err(ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED).into());
+        }
+        self.set_value(value);
+        Ok(())
+    }
+
+    /// Get the maximum amount of data that can be written in a single packet for this characteristic.
+    pub fn max_write_len(&self) -> Result<usize> {
+        // GATT characteristic writes have 3 bytes of overhead (opcode + handle id)
+        Ok(MTU - 3)
+    }
+
+    /// Get the maximum amount of data that can be written in a single packet for this characteristic.
+    pub async fn max_write_len_async(&self) -> Result<usize> {
+        self.max_write_len()
+    }
+
+    /// Enables notification of value changes for this GATT characteristic.
+    ///
+    /// Returns a stream of values for the characteristic sent from the device. The stream ends when the device
+    /// disconnects.
+    pub async fn notify(&self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + '_> {
+        self.clone().notify_owned().await
+    }
+
+    /// Like [`notify`][Self::notify], but the returned stream owns the characteristic.
+    pub async fn notify_owned(self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + 'static> {
+        DeviceState::check_connected(&self.inner.device)?;
+        if !(self.inner.properties.notify || self.inner.properties.indicate) {
+            return Err(ErrorKind::NotSupported.into());
+        }
+
+        let (sender, receiver) = mpsc::unbounded();
+        self.inner.subscribers.lock().unwrap().push(sender);
+        Ok(receiver.map(Ok))
+    }
+
+    /// Is the device currently sending notifications for this characteristic?
+    pub async fn is_notifying(&self) -> Result<bool> {
+        let mut subscribers = self.inner.subscribers.lock().unwrap();
+        subscribers.retain(|x| !x.is_closed());
+        Ok(!subscribers.is_empty())
+    }
+
+    /// Discover the descriptors associated with this characteristic.
+    pub async fn discover_descriptors(&self) -> Result<Vec<Descriptor>> {
+        self.descriptors().await
+    }
+
+    /// Get previously discovered descriptors.
+    ///
+    /// If no descriptors have been discovered yet, this method will perform descriptor discovery.
+    pub async fn descriptors(&self) -> Result<Vec<Descriptor>> {
+        DeviceState::check_connected(&self.inner.device)?;
+        Ok(self
+            .inner
+            .descriptors
+            .iter()
+            .map(|x| Descriptor::new(x.clone()))
+            .collect())
+    }
+
+    pub(super) fn set_value(&self, value: &[u8]) {
+        *self.inner.value.lock().unwrap() = value.to_vec();
+    }
+
+    pub(super) fn send_notification(&self, value: &[u8]) {
+        broadcast(&self.inner.subscribers, value.to_vec());
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use super::device::DeviceState;
+use super::MockDescriptor;
+use crate::{Descriptor, Result, Uuid};
+
+/// A Bluetooth GATT descriptor
+#[derive(Clone)]
+pub struct DescriptorImpl {
+    inner: Arc<DescriptorState>,
+}
+
+pub(super) struct DescriptorState {
+    device: Weak<DeviceState>,
+    uuid: Uuid,
+    value: Mutex<Vec<u8>>,
+}
+
+impl std::fmt::Debug for DescriptorImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Descriptor").field("uuid", &self.inner.uuid).finish()
+    }
+}
+
+impl PartialEq for DescriptorImpl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for DescriptorImpl {}
+
+impl std::hash::Hash for DescriptorImpl {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(Arc::as_ptr(&self.inner), state);
+    }
+}
+
+impl Descriptor {
+    pub(super) fn new(inner: Arc<DescriptorState>) -> Descriptor {
+        Descriptor(DescriptorImpl { inner })
+    }
+}
+
+impl DescriptorState {
+    pub(super) fn new(device: Weak<DeviceState>, descriptor: MockDescriptor) -> Arc<Self> {
+        Arc::new(DescriptorState {
+            device,
+            uuid: descriptor.uuid,
+            value: Mutex::new(descriptor.value),
+        })
+    }
+}
+
+impl DescriptorImpl {
+    /// The [`Uuid`] identifying the type of this GATT descriptor
+    pub fn uuid(&self) -> Uuid {
+        self.inner.uuid
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT descriptor
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        Ok(self.uuid())
+    }
+
+    /// The cached value of this descriptor
+    ///
+    /// If the value has not yet been read, this method may either return an error or perform a read of the value.
+    pub async fn value(&self) -> Result<Vec<u8>> {
+        Ok(self.inner.value.lock().unwrap().clone())
+    }
+
+    /// Read the value of this descriptor from the device
+    pub async fn read(&self) -> Result<Vec<u8>> {
+        DeviceState::check_connected(&self.inner.device)?;
+        self.value().await
+    }
+
+    /// Write the value of this descriptor on the device to `value`
+    pub async fn write(&self, value: &[u8]) -> Result<()> {
+        DeviceState::check_connected(&self.inner.device)?;
+        self.set_value(value);
+        Ok(())
+    }
+
+    pub(super) fn set_value(&self, value: &[u8]) {
+        *self.inner.value.lock().unwrap() = value.to_vec();
+    }
+}
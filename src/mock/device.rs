@@ -0,0 +1,512 @@
+#[cfg(feature = "l2cap")]
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+
+use futures_channel::mpsc::{self, UnboundedSender};
+use futures_core::Stream;
+use futures_lite::StreamExt;
+
+use super::adapter::AdapterState;
+use super::service::ServiceState;
+use super::{broadcast, DeviceId, MockPeripheral, MockService};
+use crate::device::ServicesChanged;
+use crate::error::ErrorKind;
+use crate::pairing::{PairingAgent, PairingMethod, PairingResult};
+use crate::{
+    AdvertisementData, AdvertisingDevice, ConnectionEvent, Device, DeviceEvent, DeviceProperty, DisconnectReason,
+    Error, Result, SecurityLevel, Service, Uuid,
+};
+
+/// A Bluetooth LE device
+#[derive(Clone)]
+pub struct DeviceImpl {
+    inner: Arc<DeviceState>,
+}
+
+pub(super) struct DeviceState {
+    pub(super) id: DeviceId,
+    adapter: Weak<AdapterState>,
+    data: Mutex<DeviceData>,
+    connection_events: Mutex<Vec<UnboundedSender<ConnectionEvent>>>,
+    property_changes: Mutex<Vec<UnboundedSender<DeviceProperty>>>,
+    services_changed: Mutex<Vec<UnboundedSender<ServicesChanged>>>,
+    #[cfg(feature = "l2cap")]
+    l2cap_listeners: Mutex<HashMap<u16, UnboundedSender<super::l2cap_channel::Channel>>>,
+    #[cfg(feature = "l2cap")]
+    l2cap_packet_listeners: Mutex<HashMap<u16, UnboundedSender<super::l2cap_channel::PacketChannel>>>,
+}
+
+struct DeviceData {
+    name: Option<String>,
+    adv_data: AdvertisementData,
+    rssi: Option<i16>,
+    connected: bool,
+    paired: bool,
+    /// The security level of connections established with the keys from pairing
+    pairing_security: SecurityLevel,
+    services: Vec<Arc<ServiceState>>,
+}
+
+impl std::fmt::Debug for DeviceImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Device").field(&self.inner.id).finish()
+    }
+}
+
+impl std::fmt::Debug for DeviceState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DeviceState").field(&self.id).finish()
+    }
+}
+
+impl PartialEq for DeviceImpl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for DeviceImpl {}
+
+impl std::hash::Hash for DeviceImpl {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inner.id.hash(state);
+    }
+}
+
+impl std::fmt::Display for DeviceImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name().as_deref().unwrap_or("(Unknown)"))
+    }
+}
+
+impl DeviceState {
+    pub(super) fn new(adapter: Weak<AdapterState>, peripheral: MockPeripheral) -> Arc<Self> {
+        Arc::new_cyclic(|this| DeviceState {
+            id: DeviceId::next(),
+            adapter,
+            data: Mutex::new(DeviceData {
+                name: peripheral.name,
+                adv_data: peripheral.adv_data,
+                rssi: peripheral.rssi,
+                connected: false,
+                paired: peripheral.paired,
+                pairing_security: if peripheral.paired {
+                    SecurityLevel::Medium
+                } else {
+                    SecurityLevel::Low
+                },
+                services: ServiceState::build(this, peripheral.services),
+            }),
+            connection_events: Default::default(),
+            property_changes: Default::default(),
+            services_changed: Default::default(),
+            #[cfg(feature = "l2cap")]
+            l2cap_listeners: Default::default(),
+            #[cfg(feature = "l2cap")]
+            l2cap_packet_listeners: Default::default(),
+        })
+    }
+
+    /// Returns an error unless the device is currently connected
+    pub(super) fn check_connected(device: &Weak<DeviceState>) -> Result<()> {
+        match device.upgrade() {
+            Some(device) if device.data.lock().unwrap().connected => Ok(()),
+            _ => Err(ErrorKind::NotConnected.into()),
+        }
+    }
+}
+
+impl DeviceImpl {
+    pub(super) fn new(inner: Arc<DeviceState>) -> Self {
+        DeviceImpl { inner }
+    }
+
+    /// This device's unique identifier
+    pub fn id(&self) -> DeviceId {
+        self.inner.id
+    }
+
+    /// The local name for this device, if available
+    ///
+    /// This can either be a name advertised or read from the device, or a name assigned to the device by the OS.
+    pub fn name(&self) -> Result<String> {
+        let data = self.inner.data.lock().unwrap();
+        data.name
+            .clone()
+            .or_else(|| data.adv_data.local_name.clone())
+            .ok_or_else(|| ErrorKind::NotFound.into())
+    }
+
+    /// The local name for this device, if available
+    ///
+    /// This can either be a name advertised or read from the device, or a name assigned to the device by the OS.
+    pub async fn name_async(&self) -> Result<String> {
+        self.name()
+    }
+
+    /// The mock backend does not distinguish an alias from the name
+    pub async fn alias(&self) -> Result<String> {
+        self.name()
+    }
+
+    /// The connection status for this device
+    pub async fn is_connected(&self) -> bool {
+        self.is_connected_now()
+    }
+
+    /// The pairing status for this device
+    pub async fn is_paired(&self) -> Result<bool> {
+        Ok(self.inner.data.lock().unwrap().paired)
+    }
+
+    /// Attempt to pair this device using the system default pairing UI
+    ///
+    /// The mock backend performs "JustWorks" pairing.
+    pub async fn pair(&self) -> Result<PairingResult> {
+        Ok(self.pair_with_method(PairingMethod::JustWorks))
+    }
+
+    /// Attempt to pair this device using the system default pairing UI
+    ///
+    /// The mock backend performs "JustWorks" pairing, so only [`PairingAgent::confirm`] is called.
+    pub async fn pair_with_agent<T: PairingAgent + 'static>(&self, agent: &T) -> Result<PairingResult> {
+        if self.is_paired().await? {
+            return self.pair().await;
+        }
+
+        let device = Device(self.clone());
+        agent
+            .confirm(&device)
+            .await
+            .map_err(|err| Error::new(ErrorKind::NotAuthorized, Some(Box::new(err)), "pairing".to_string()))?;
+        self.pair().await
+    }
+
+    fn pair_with_method(&self, method: PairingMethod) -> PairingResult {
+        let mut data = self.inner.data.lock().unwrap();
+        if data.paired {
+            return PairingResult {
+                bonded: true,
+                method: None,
+                security_level: Some(data.pairing_security),
+            };
+        }
+
+        data.paired = true;
+        data.pairing_security = if method.is_authenticated() {
+            SecurityLevel::High
+        } else {
+            SecurityLevel::Medium
+        };
+        let security_level = data.pairing_security;
+        drop(data);
+
+        broadcast(&self.inner.property_changes, DeviceProperty::Paired(true));
+        self.device_event(DeviceEvent::Paired(Device(self.clone())));
+        PairingResult {
+            bonded: true,
+            method: Some(method),
+            security_level: Some(security_level),
+        }
+    }
+
+    /// Disconnect and unpair this device from the system
+    pub async fn unpair(&self) -> Result<()> {
+        self.disconnect(DisconnectReason::LocalHost);
+        let was_paired = std::mem::replace(&mut self.inner.data.lock().unwrap().paired, false);
+        if was_paired {
+            broadcast(&self.inner.property_changes, DeviceProperty::Paired(false));
+            self.device_event(DeviceEvent::Unpaired(Device(self.clone())));
+        }
+        Ok(())
+    }
+
+    /// The security level of the current connection to this device
+    ///
+    /// Connections to paired devices are encrypted with the keys from pairing.
+    pub async fn security_level(&self) -> Result<SecurityLevel> {
+        let data = self.inner.data.lock().unwrap();
+        match (data.connected, data.paired) {
+            (false, _) => Err(ErrorKind::NotConnected.into()),
+            (true, true) => Ok(data.pairing_security),
+            (true, false) => Ok(SecurityLevel::Low),
+        }
+    }
+
+    /// Whether the current connection to this device is encrypted
+    pub async fn is_encrypted(&self) -> Result<bool> {
+        let data = self.inner.data.lock().unwrap();
+        Ok(data.connected && data.paired && data.pairing_security > SecurityLevel::Low)
+    }
+
+    /// Discover the primary services of this device.
+    pub async fn discover_services(&self) -> Result<Vec<Service>> {
+        self.services().await
+    }
+
+    /// Discover the primary service(s) of this device with the given [`Uuid`].
+    pub async fn discover_services_with_uuid(&self, uuid: Uuid) -> Result<Vec<Service>> {
+        Ok(self
+            .services()
+            .await?
+            .into_iter()
+            .filter(|x| x.uuid() == uuid)
+            .collect())
+    }
+
+    /// Get previously discovered services.
+    ///
+    /// If no services have been discovered yet, this method will perform service discovery.
+    pub async fn services(&self) -> Result<Vec<Service>> {
+        DeviceState::check_connected(&Arc::downgrade(&self.inner))?;
+        Ok(self
+            .inner
+            .data
+            .lock()
+            .unwrap()
+            .services
+            .iter()
+            .map(|x| Service::new(x.clone()))
+            .collect())
+    }
+
+    /// Monitors the device for services changed events.
+    pub async fn service_changed_indications(
+        &self,
+    ) -> Result<impl Stream<Item = Result<ServicesChanged>> + Send + Unpin + '_> {
+        DeviceState::check_connected(&Arc::downgrade(&self.inner))?;
+        let (sender, receiver) = mpsc::unbounded();
+        self.inner.services_changed.lock().unwrap().push(sender);
+        Ok(receiver.map(Ok))
+    }
+
+    /// Monitors the device for changes of its properties.
+    pub async fn property_changes(&self) -> Result<impl Stream<Item = DeviceProperty> + Send + Unpin + '_> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.inner.property_changes.lock().unwrap().push(sender);
+        Ok(receiver)
+    }
+
+    /// Get the current signal strength from the device in dBm.
+    pub async fn rssi(&self) -> Result<i16> {
+        self.inner
+            .data
+            .lock()
+            .unwrap()
+            .rssi
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, None, "no signal strength available".to_string()))
+    }
+
+    /// Get the signal strength of the most recent advertisement, like [`rssi`][Self::rssi].
+    pub async fn refresh_rssi(&self) -> Result<i16> {
+        self.rssi().await
+    }
+
+    /// Open an L2CAP connection-oriented channel to a listener registered with
+    /// [`listen_l2cap`][crate::mock::listen_l2cap].
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_channel(
+        &self,
+        psm: u16,
+        security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::Channel> {
+        DeviceState::check_connected(&Arc::downgrade(&self.inner))?;
+
+        let (local, remote) = super::l2cap_channel::Channel::pair(psm, self.id(), security);
+        super::l2cap_channel::connect(&self.inner.l2cap_listeners, psm, remote)?;
+        Ok(local)
+    }
+
+    /// Open an L2CAP connection-oriented channel in sequential packet mode to a listener registered with
+    /// [`listen_l2cap_packets`][crate::mock::listen_l2cap_packets].
+    #[cfg(feature = "l2cap")]
+    pub async fn open_l2cap_packet_channel(
+        &self,
+        psm: u16,
+        _security: crate::L2CapSecurity,
+    ) -> Result<super::l2cap_channel::PacketChannel> {
+        DeviceState::check_connected(&Arc::downgrade(&self.inner))?;
+
+        let (local, remote) = super::l2cap_channel::PacketChannel::pair();
+        super::l2cap_channel::connect(&self.inner.l2cap_packet_listeners, psm, remote)?;
+        Ok(local)
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub(super) fn open_l2cap_to_adapter(&self, psm: u16) -> Result<super::l2cap_channel::Channel> {
+        DeviceState::check_connected(&Arc::downgrade(&self.inner))?;
+        let adapter = self.adapter().ok_or(ErrorKind::AdapterUnavailable)?;
+        adapter.open_l2cap(psm, Device(self.clone()))
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub(super) fn listen_l2cap(&self, psm: u16) -> mpsc::UnboundedReceiver<super::l2cap_channel::Channel> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.inner.l2cap_listeners.lock().unwrap().insert(psm, sender);
+        receiver
+    }
+
+    #[cfg(feature = "l2cap")]
+    pub(super) fn listen_l2cap_packets(
+        &self,
+        psm: u16,
+    ) -> mpsc::UnboundedReceiver<super::l2cap_channel::PacketChannel> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.inner.l2cap_packet_listeners.lock().unwrap().insert(psm, sender);
+        receiver
+    }
+
+    pub(super) fn is(&self, state: &Arc<DeviceState>) -> bool {
+        Arc::ptr_eq(&self.inner, state)
+    }
+
+    pub(super) fn adapter(&self) -> Option<Arc<AdapterState>> {
+        self.inner.adapter.upgrade()
+    }
+
+    pub(super) fn is_paired_now(&self) -> bool {
+        self.inner.data.lock().unwrap().paired
+    }
+
+    pub(super) fn is_connected_now(&self) -> bool {
+        self.inner.data.lock().unwrap().connected
+    }
+
+    pub(super) fn has_any_service(&self, services: &[Uuid]) -> bool {
+        let data = self.inner.data.lock().unwrap();
+        data.services.iter().any(|x| services.contains(&x.uuid))
+    }
+
+    pub(super) fn advertisement(&self) -> AdvertisingDevice {
+        let (adv_data, rssi) = {
+            let data = self.inner.data.lock().unwrap();
+            (data.adv_data.clone(), data.rssi)
+        };
+        AdvertisingDevice {
+            device: Device(self.clone()),
+            adv_data,
+            rssi,
+        }
+    }
+
+    pub(super) fn connection_events(&self) -> impl Stream<Item = ConnectionEvent> + Send + Unpin {
+        let (sender, receiver) = mpsc::unbounded();
+        self.inner.connection_events.lock().unwrap().push(sender);
+        receiver
+    }
+
+    pub(super) fn connect(&self) {
+        let was_connected = std::mem::replace(&mut self.inner.data.lock().unwrap().connected, true);
+        if !was_connected {
+            broadcast(&self.inner.connection_events, ConnectionEvent::Connected);
+            self.device_event(DeviceEvent::Connected(Device(self.clone())));
+        }
+    }
+
+    pub(super) fn disconnect(&self, reason: DisconnectReason) {
+        let services = {
+            let mut data = self.inner.data.lock().unwrap();
+            if !std::mem::replace(&mut data.connected, false) {
+                return;
+            }
+            data.services.clone()
+        };
+
+        for service in services {
+            service.unsubscribe_all();
+        }
+        self.inner.services_changed.lock().unwrap().clear();
+        broadcast(&self.inner.connection_events, ConnectionEvent::Disconnected { reason });
+        self.device_event(DeviceEvent::Disconnected {
+            device: Device(self.clone()),
+            reason,
+        });
+    }
+
+    pub(super) fn set_name(&self, name: Option<String>) {
+        let old_name = self.name().ok();
+        self.inner.data.lock().unwrap().name = name;
+        self.name_changed(old_name);
+    }
+
+    fn name_changed(&self, old_name: Option<String>) {
+        match self.name() {
+            Ok(name) if old_name.as_ref() != Some(&name) => {
+                broadcast(&self.inner.property_changes, DeviceProperty::Name(name.clone()));
+                self.device_event(DeviceEvent::NameChanged {
+                    device: Device(self.clone()),
+                    name,
+                });
+            }
+            _ => (),
+        }
+    }
+
+    fn device_event(&self, event: DeviceEvent) {
+        if let Some(adapter) = self.adapter() {
+            adapter.device_event(event);
+        }
+    }
+
+    pub(super) fn set_adv_data(&self, adv_data: AdvertisementData, rssi: Option<i16>) {
+        let old_name = self.name().ok();
+        let (old_adv_data, old_rssi) = {
+            let mut data = self.inner.data.lock().unwrap();
+            (
+                std::mem::replace(&mut data.adv_data, adv_data.clone()),
+                std::mem::replace(&mut data.rssi, rssi),
+            )
+        };
+
+        let mut changes = Vec::new();
+        if let Some(rssi) = rssi.filter(|&x| Some(x) != old_rssi) {
+            changes.push(DeviceProperty::Rssi(rssi));
+        }
+        if let Some(tx_power) = adv_data
+            .tx_power_level
+            .filter(|&x| Some(x) != old_adv_data.tx_power_level)
+        {
+            changes.push(DeviceProperty::TxPower(tx_power));
+        }
+        if adv_data.all_manufacturer_data != old_adv_data.all_manufacturer_data {
+            changes.push(DeviceProperty::ManufacturerData(adv_data.all_manufacturer_data));
+        }
+        if adv_data.service_data != old_adv_data.service_data {
+            changes.push(DeviceProperty::ServiceData(adv_data.service_data));
+        }
+        for change in changes {
+            broadcast(&self.inner.property_changes, change);
+        }
+        self.name_changed(old_name);
+    }
+
+    pub(super) fn set_services(&self, services: Vec<MockService>) {
+        let services = ServiceState::build(&Arc::downgrade(&self.inner), services);
+        let (old, connected) = {
+            let mut data = self.inner.data.lock().unwrap();
+            (std::mem::replace(&mut data.services, services), data.connected)
+        };
+
+        let mut handles = Vec::new();
+        for service in old {
+            service.unsubscribe_all();
+            service.collect_handles(&mut handles);
+        }
+
+        if connected {
+            broadcast(
+                &self.inner.services_changed,
+                ServicesChanged(ServicesChangedImpl(handles)),
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServicesChangedImpl(Vec<u16>);
+
+impl ServicesChangedImpl {
+    pub fn was_invalidated(&self, service: &Service) -> bool {
+        self.0.contains(&service.0.handle())
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::pin::{pin, Pin};
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+use futures_channel::mpsc;
+use futures_lite::{future, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
+
+use super::DeviceId;
+use crate::error::ErrorKind;
+use crate::{Device, Error, L2CapChannelImpl, L2CapFlowControl, L2CapSecurity};
+
+pub type Reader = tokio::io::ReadHalf<DuplexStream>;
+pub type Writer = tokio::io::WriteHalf<DuplexStream>;
+
+/// The number of bytes buffered in each direction of a mock channel
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// The MTU of mock channels in both directions
+const MTU: usize = 512;
+
+#[derive(Debug)]
+pub struct Channel {
+    stream: DuplexStream,
+    psm: u16,
+    /// The peripheral on the other end, or `None` for the peripheral's end of the channel
+    peer: Option<DeviceId>,
+    security: L2CapSecurity,
+}
+
+impl Channel {
+    /// Creates both ends of a connected in-memory channel between the adapter and the peripheral `peer`
+    ///
+    /// The first channel is the adapter's end and the second is the peripheral's end.
+    pub(super) fn pair(psm: u16, peer: DeviceId, security: L2CapSecurity) -> (Self, Self) {
+        let (a, b) = tokio::io::duplex(BUFFER_SIZE);
+        let a = Channel {
+            stream: a,
+            psm,
+            peer: Some(peer),
+            security,
+        };
+        let b = Channel {
+            stream: b,
+            psm,
+            peer: None,
+            security,
+        };
+        (a, b)
+    }
+}
+
+impl L2CapChannelImpl for Channel {
+    fn split(self) -> (crate::L2CapReader, crate::L2CapWriter) {
+        let (reader, writer) = tokio::io::split(self.stream);
+        (crate::L2CapReader(reader), crate::L2CapWriter(writer))
+    }
+
+    fn psm(&self) -> crate::Result<u16> {
+        Ok(self.psm)
+    }
+
+    fn peer(&self) -> crate::Result<DeviceId> {
+        self.peer.ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "the peer of a peripheral's channel is the adapter".to_string(),
+            )
+        })
+    }
+
+    fn send_mtu(&self) -> crate::Result<usize> {
+        Ok(MTU)
+    }
+
+    fn recv_mtu(&self) -> crate::Result<usize> {
+        Ok(MTU)
+    }
+
+    fn security(&self) -> crate::Result<L2CapSecurity> {
+        Ok(self.security)
+    }
+
+    fn flow_control(&self) -> crate::Result<L2CapFlowControl> {
+        Ok(L2CapFlowControl::LeCredit)
+    }
+}
+
+impl AsyncRead for Channel {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        pin!(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Channel {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        pin!(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        pin!(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        pin!(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+#[derive(Debug)]
+pub struct Listener {
+    psm: u16,
+    security: L2CapSecurity,
+    receiver: Mutex<mpsc::UnboundedReceiver<(Channel, Device)>>,
+}
+
+impl Listener {
+    pub(super) fn new(psm: u16, security: L2CapSecurity, receiver: mpsc::UnboundedReceiver<(Channel, Device)>) -> Self {
+        Listener {
+            psm,
+            security,
+            receiver: Mutex::new(receiver),
+        }
+    }
+
+    pub fn psm(&self) -> u16 {
+        self.psm
+    }
+
+    pub async fn accept(&self) -> crate::Result<(Channel, Device)> {
+        let (mut channel, device) = future::poll_fn(|cx| self.receiver.lock().unwrap().poll_next(cx))
+            .await
+            .ok_or(ErrorKind::AdapterUnavailable)?;
+        channel.security = self.security;
+        Ok((channel, device))
+    }
+}
+
+#[derive(Debug)]
+pub struct PacketChannel {
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+    receiver: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+impl PacketChannel {
+    /// Creates both ends of a connected in-memory packet channel
+    pub(super) fn pair() -> (Self, Self) {
+        let (a_sender, b_receiver) = mpsc::unbounded();
+        let (b_sender, a_receiver) = mpsc::unbounded();
+        let a = PacketChannel {
+            sender: a_sender,
+            receiver: Mutex::new(a_receiver),
+        };
+        let b = PacketChannel {
+            sender: b_sender,
+            receiver: Mutex::new(b_receiver),
+        };
+        (a, b)
+    }
+
+    pub async fn send(&self, packet: &[u8]) -> crate::Result<()> {
+        if packet.len() > MTU {
+            return Err(Error::new(
+                ErrorKind::InvalidParameter,
+                None,
+                format!("packet of {} bytes exceeds the MTU of {MTU} bytes", packet.len()),
+            ));
+        }
+        self.sender
+            .unbounded_send(packet.to_vec())
+            .map_err(|_| Error::new(ErrorKind::NotConnected, None, "channel closed".to_string()))
+    }
+
+    pub async fn recv(&self) -> crate::Result<Vec<u8>> {
+        future::poll_fn(|cx| self.receiver.lock().unwrap().poll_next(cx))
+            .await
+            .ok_or_else(|| Error::new(ErrorKind::NotConnected, None, "channel closed".to_string()))
+    }
+
+    pub fn send_mtu(&self) -> crate::Result<usize> {
+        Ok(MTU)
+    }
+
+    pub fn recv_mtu(&self) -> crate::Result<usize> {
+        Ok(MTU)
+    }
+}
+
+/// Sends `channel` to the listener on `psm`, removing the listener if it has been dropped
+pub(super) fn connect<T>(
+    listeners: &Mutex<HashMap<u16, mpsc::UnboundedSender<T>>>,
+    psm: u16,
+    channel: T,
+) -> crate::Result<()> {
+    let mut listeners = listeners.lock().unwrap();
+    match listeners.get(&psm).map(|x| x.unbounded_send(channel)) {
+        Some(Ok(())) => return Ok(()),
+        Some(Err(_)) => {
+            listeners.remove(&psm);
+        }
+        None => (),
+    }
+    Err(Error::new(
+        ErrorKind::ConnectionFailed,
+        None,
+        format!("no listener for PSM {psm}"),
+    ))
+}
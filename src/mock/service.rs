@@ -0,0 +1,171 @@
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Weak};
+
+use super::characteristic::CharacteristicState;
+use super::device::DeviceState;
+use super::MockService;
+use crate::{Characteristic, Result, Service, Uuid};
+
+/// A Bluetooth GATT service
+#[derive(Clone)]
+pub struct ServiceImpl {
+    inner: Arc<ServiceState>,
+}
+
+pub(super) struct ServiceState {
+    handle: u16,
+    device: Weak<DeviceState>,
+    pub(super) uuid: Uuid,
+    is_primary: bool,
+    characteristics: Vec<Arc<CharacteristicState>>,
+    included_services: Vec<Arc<ServiceState>>,
+}
+
+impl std::fmt::Debug for ServiceImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Service")
+            .field("handle", &self.inner.handle)
+            .field("uuid", &self.inner.uuid)
+            .finish()
+    }
+}
+
+impl PartialEq for ServiceImpl {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for ServiceImpl {}
+
+impl std::hash::Hash for ServiceImpl {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inner.handle.hash(state);
+    }
+}
+
+impl Service {
+    pub(super) fn new(inner: Arc<ServiceState>) -> Service {
+        Service(ServiceImpl { inner })
+    }
+}
+
+impl ServiceState {
+    /// Creates the attribute tree for `services`, assigning each service a unique handle
+    pub(super) fn build(device: &Weak<DeviceState>, services: Vec<MockService>) -> Vec<Arc<ServiceState>> {
+        static NEXT_HANDLE: AtomicU16 = AtomicU16::new(1);
+
+        services
+            .into_iter()
+            .map(|service| {
+                Arc::new(ServiceState {
+                    handle: NEXT_HANDLE.fetch_add(1, Ordering::Relaxed),
+                    device: device.clone(),
+                    uuid: service.uuid,
+                    is_primary: service.is_primary,
+                    characteristics: service
+                        .characteristics
+                        .into_iter()
+                        .map(|x| CharacteristicState::new(device.clone(), x))
+                        .collect(),
+                    included_services: ServiceState::build(device, service.included_services),
+                })
+            })
+            .collect()
+    }
+
+    /// Ends all notification streams of this service and its included services
+    pub(super) fn unsubscribe_all(&self) {
+        for characteristic in &self.characteristics {
+            characteristic.unsubscribe_all();
+        }
+        for service in &self.included_services {
+            service.unsubscribe_all();
+        }
+    }
+
+    /// Appends the handles of this service and its included services to `handles`
+    pub(super) fn collect_handles(&self, handles: &mut Vec<u16>) {
+        handles.push(self.handle);
+        for service in &self.included_services {
+            service.collect_handles(handles);
+        }
+    }
+}
+
+impl ServiceImpl {
+    /// The [`Uuid`] identifying the type of this GATT service
+    pub fn uuid(&self) -> Uuid {
+        self.inner.uuid
+    }
+
+    /// The [`Uuid`] identifying the type of this GATT service
+    pub async fn uuid_async(&self) -> Result<Uuid> {
+        Ok(self.uuid())
+    }
+
+    /// Whether this is a primary service of the device.
+    pub async fn is_primary(&self) -> Result<bool> {
+        Ok(self.inner.is_primary)
+    }
+
+    /// Discover all characteristics associated with this service.
+    pub async fn discover_characteristics(&self) -> Result<Vec<Characteristic>> {
+        self.characteristics().await
+    }
+
+    /// Discover the characteristic(s) with the given [`Uuid`].
+    pub async fn discover_characteristics_with_uuid(&self, uuid: Uuid) -> Result<Vec<Characteristic>> {
+        Ok(self
+            .characteristics()
+            .await?
+            .into_iter()
+            .filter(|x| x.uuid() == uuid)
+            .collect())
+    }
+
+    /// Get previously discovered characteristics.
+    ///
+    /// If no characteristics have been discovered yet, this method will perform characteristic discovery.
+    pub async fn characteristics(&self) -> Result<Vec<Characteristic>> {
+        DeviceState::check_connected(&self.inner.device)?;
+        Ok(self
+            .inner
+            .characteristics
+            .iter()
+            .map(|x| Characteristic::new(x.clone()))
+            .collect())
+    }
+
+    /// Discover the included services of this service.
+    pub async fn discover_included_services(&self) -> Result<Vec<Service>> {
+        self.included_services().await
+    }
+
+    /// Discover the included service(s) with the given [`Uuid`].
+    pub async fn discover_included_services_with_uuid(&self, uuid: Uuid) -> Result<Vec<Service>> {
+        Ok(self
+            .included_services()
+            .await?
+            .into_iter()
+            .filter(|x| x.uuid() == uuid)
+            .collect())
+    }
+
+    /// Get previously discovered included services.
+    ///
+    /// If no included services have been discovered yet, this method will perform included service discovery.
+    pub async fn included_services(&self) -> Result<Vec<Service>> {
+        DeviceState::check_connected(&self.inner.device)?;
+        Ok(self
+            .inner
+            .included_services
+            .iter()
+            .map(|x| Service::new(x.clone()))
+            .collect())
+    }
+
+    pub(super) fn handle(&self) -> u16 {
+        self.inner.handle
+    }
+}
@@ -1,8 +1,13 @@
 //! Custom Bluetooth pairing agent.
 
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
 use async_trait::async_trait;
+use futures_channel::{mpsc, oneshot};
+use futures_core::Stream;
 
-use crate::Device;
+use crate::{Device, SecurityLevel, Uuid};
 
 /// Bluetooth input/output capabilities for pairing
 ///
@@ -95,6 +100,44 @@ impl std::str::FromStr for Passkey {
     }
 }
 
+/// The association model used to pair with a device
+///
+/// See the Bluetooth Core Specification, Vol 3, Part H, §2.3.5.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum PairingMethod {
+    /// Unauthenticated pairing without user interaction ("JustWorks")
+    JustWorks,
+    /// The user confirms that both devices display the same 6-digit value (LE Secure Connections only)
+    NumericComparison,
+    /// The user enters a 6-digit passkey displayed by one device on the other device
+    PasskeyEntry,
+    /// The devices are authenticated using data exchanged over another channel, such as NFC
+    OutOfBand,
+    /// Legacy (pre-Bluetooth 2.1) PIN code pairing
+    LegacyPin,
+}
+
+impl PairingMethod {
+    /// Returns `true` if this method protects from man-in-the-middle attacks
+    pub fn is_authenticated(&self) -> bool {
+        !matches!(self, PairingMethod::JustWorks)
+    }
+}
+
+/// The outcome of a successful pairing operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PairingResult {
+    /// Whether the keys exchanged during pairing were stored by the system (bonding), so that later connections are
+    /// encrypted without pairing again. `false` if the pairing only applies to the current connection.
+    pub bonded: bool,
+    /// The association model used to pair, or `None` if it was not reported by the platform or the device was already
+    /// paired
+    pub method: Option<PairingMethod>,
+    /// The security level of the link after pairing, or `None` if it was not reported by the platform
+    pub security_level: Option<SecurityLevel>,
+}
+
 /// A custom pairing agent responsible for interacting with the user during the peripheral pairing process.
 #[async_trait]
 pub trait PairingAgent: Send + Sync {
@@ -129,6 +172,33 @@ pub trait PairingAgent: Send + Sync {
     ///
     /// Must be supported if `io_capability` is `DisplayOnly`, `DisplayYesNo`, or `KeyboardDisplay`
     fn display_passkey(&self, _device: &Device, _passkey: Passkey) {}
+
+    /// Request a PIN code from the user for legacy (pre-Bluetooth 2.1) pairing.
+    ///
+    /// The PIN code must be an alphanumeric string of 1 to 16 characters.
+    async fn request_pin_code(&self, _device: &Device) -> Result<String, PairingRejected> {
+        Err(PairingRejected)
+    }
+
+    /// Display a PIN code to the user for legacy (pre-Bluetooth 2.1) pairing.
+    ///
+    /// The PIN code should be displayed until the async pair operation that triggered this method completes or is
+    /// cancelled.
+    fn display_pin_code(&self, _device: &Device, _pin_code: &str) {}
+
+    /// Request authorization from the user for a pairing attempt initiated by the remote device which would
+    /// otherwise be accepted without confirmation ("JustWorks").
+    ///
+    /// The default implementation calls [`confirm`][Self::confirm].
+    async fn authorize(&self, device: &Device) -> Result<(), PairingRejected> {
+        self.confirm(device).await
+    }
+
+    /// Request authorization from the user for the remote device to connect to the local service identified by
+    /// `service`.
+    async fn authorize_service(&self, _device: &Device, _service: Uuid) -> Result<(), PairingRejected> {
+        Err(PairingRejected)
+    }
 }
 
 /// The simplest possible pairing agent.
@@ -148,3 +218,260 @@ impl PairingAgent for NoInputOutputPairingAgent {
         Ok(())
     }
 }
+
+/// A pairing agent which always uses the same passkey.
+///
+/// This agent enters its passkey when asked for one, only confirms passkeys matching its own, and automatically
+/// confirms "JustWorks" pairing requests. It is intended for tests and for devices with a fixed, known passkey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedPasskeyAgent {
+    passkey: Passkey,
+}
+
+impl FixedPasskeyAgent {
+    /// Creates a new `FixedPasskeyAgent` using `passkey`
+    pub fn new(passkey: Passkey) -> Self {
+        FixedPasskeyAgent { passkey }
+    }
+
+    /// The passkey used by this agent
+    pub fn passkey(&self) -> Passkey {
+        self.passkey
+    }
+}
+
+#[async_trait]
+impl PairingAgent for FixedPasskeyAgent {
+    fn io_capability(&self) -> IoCapability {
+        IoCapability::KeyboardOnly
+    }
+
+    async fn confirm(&self, _device: &Device) -> Result<(), PairingRejected> {
+        Ok(())
+    }
+
+    async fn confirm_passkey(&self, _device: &Device, passkey: Passkey) -> Result<(), PairingRejected> {
+        if passkey == self.passkey {
+            Ok(())
+        } else {
+            Err(PairingRejected)
+        }
+    }
+
+    async fn request_passkey(&self, _device: &Device) -> Result<Passkey, PairingRejected> {
+        Ok(self.passkey)
+    }
+}
+
+/// A request forwarded by a [`ChannelPairingAgent`]
+///
+/// Requests expecting an answer carry a [`PairingReply`]. Dropping the reply without answering rejects the request.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PairingRequest {
+    /// See [`PairingAgent::confirm`]
+    Confirm {
+        /// The device being paired
+        device: Device,
+        /// Handle to accept or reject the request
+        reply: PairingReply<()>,
+    },
+    /// See [`PairingAgent::confirm_passkey`]
+    ConfirmPasskey {
+        /// The device being paired
+        device: Device,
+        /// The passkey to display for validation
+        passkey: Passkey,
+        /// Handle to accept or reject the request
+        reply: PairingReply<()>,
+    },
+    /// See [`PairingAgent::request_passkey`]
+    RequestPasskey {
+        /// The device being paired
+        device: Device,
+        /// Handle to answer with the passkey entered by the user or to reject the request
+        reply: PairingReply<Passkey>,
+    },
+    /// See [`PairingAgent::display_passkey`]
+    DisplayPasskey {
+        /// The device being paired
+        device: Device,
+        /// The passkey to display
+        passkey: Passkey,
+    },
+    /// See [`PairingAgent::request_pin_code`]
+    RequestPinCode {
+        /// The device being paired
+        device: Device,
+        /// Handle to answer with the PIN code entered by the user or to reject the request
+        reply: PairingReply<String>,
+    },
+    /// See [`PairingAgent::display_pin_code`]
+    DisplayPinCode {
+        /// The device being paired
+        device: Device,
+        /// The PIN code to display
+        pin_code: String,
+    },
+    /// See [`PairingAgent::authorize_service`]
+    AuthorizeService {
+        /// The device requesting access
+        device: Device,
+        /// The local service the device wants to connect to
+        service: Uuid,
+        /// Handle to accept or reject the request
+        reply: PairingReply<()>,
+    },
+}
+
+/// A one-shot handle to answer a [`PairingRequest`]
+///
+/// Dropping the handle without answering rejects the request.
+#[derive(Debug)]
+pub struct PairingReply<T>(oneshot::Sender<Result<T, PairingRejected>>);
+
+impl<T> PairingReply<T> {
+    /// Accepts the request, answering with `value`
+    pub fn accept(self, value: T) {
+        let _ = self.0.send(Ok(value));
+    }
+
+    /// Rejects the request
+    pub fn reject(self) {
+        let _ = self.0.send(Err(PairingRejected));
+    }
+
+    /// Returns `true` if the pairing operation which made the request has completed or been cancelled, so that
+    /// answering it has no effect.
+    pub fn is_canceled(&self) -> bool {
+        self.0.is_canceled()
+    }
+}
+
+/// The stream of requests forwarded by a [`ChannelPairingAgent`]
+///
+/// The stream ends when the corresponding agent is dropped.
+#[derive(Debug)]
+pub struct PairingRequests(mpsc::UnboundedReceiver<PairingRequest>);
+
+impl Stream for PairingRequests {
+    type Item = PairingRequest;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// A pairing agent which forwards every request as a [`PairingRequest`] to a [`PairingRequests`] stream.
+///
+/// This allows the requests to be handled by another task, such as the event loop of a user interface. Pairing
+/// requests which are not answered keep the pairing operation waiting, and requests which are rejected, dropped, or
+/// made after the [`PairingRequests`] stream has been dropped fail the pairing operation.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use bluest::pairing::{ChannelPairingAgent, IoCapability, PairingRequest};
+/// # use futures_lite::{future, StreamExt};
+/// # async fn example(device: bluest::Device) -> bluest::Result<()> {
+/// let (agent, mut requests) = ChannelPairingAgent::new(IoCapability::DisplayYesNo);
+/// let ui = async move {
+///     while let Some(request) = requests.next().await {
+///         match request {
+///             PairingRequest::ConfirmPasskey { passkey, reply, .. } => {
+///                 println!("Confirming passkey {passkey}");
+///                 reply.accept(());
+///             }
+///             PairingRequest::DisplayPasskey { passkey, .. } => println!("Enter passkey {passkey} on the device"),
+///             _ => (),
+///         }
+///     }
+/// };
+/// // The request stream only ends when the agent is dropped, so pairing always completes first
+/// let res = future::or(device.pair_with_agent(&agent), async {
+///     ui.await;
+///     future::pending().await
+/// })
+/// .await?;
+/// println!("Paired using {:?}", res.method);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChannelPairingAgent {
+    io_capability: IoCapability,
+    sender: mpsc::UnboundedSender<PairingRequest>,
+}
+
+impl ChannelPairingAgent {
+    /// Creates a new `ChannelPairingAgent` with the given input/output capabilities, along with the stream of requests
+    /// forwarded by the agent.
+    pub fn new(io_capability: IoCapability) -> (Self, PairingRequests) {
+        let (sender, receiver) = mpsc::unbounded();
+        let agent = ChannelPairingAgent { io_capability, sender };
+        (agent, PairingRequests(receiver))
+    }
+
+    async fn request<T>(&self, request: impl FnOnce(PairingReply<T>) -> PairingRequest) -> Result<T, PairingRejected> {
+        let (sender, receiver) = oneshot::channel();
+        self.sender
+            .unbounded_send(request(PairingReply(sender)))
+            .map_err(|_| PairingRejected)?;
+        receiver.await.unwrap_or(Err(PairingRejected))
+    }
+}
+
+#[async_trait]
+impl PairingAgent for ChannelPairingAgent {
+    fn io_capability(&self) -> IoCapability {
+        self.io_capability
+    }
+
+    async fn confirm(&self, device: &Device) -> Result<(), PairingRejected> {
+        let device = device.clone();
+        self.request(|reply| PairingRequest::Confirm { device, reply }).await
+    }
+
+    async fn confirm_passkey(&self, device: &Device, passkey: Passkey) -> Result<(), PairingRejected> {
+        let device = device.clone();
+        self.request(|reply| PairingRequest::ConfirmPasskey { device, passkey, reply })
+            .await
+    }
+
+    async fn request_passkey(&self, device: &Device) -> Result<Passkey, PairingRejected> {
+        let device = device.clone();
+        self.request(|reply| PairingRequest::RequestPasskey { device, reply })
+            .await
+    }
+
+    fn display_passkey(&self, device: &Device, passkey: Passkey) {
+        let device = device.clone();
+        let _ = self
+            .sender
+            .unbounded_send(PairingRequest::DisplayPasskey { device, passkey });
+    }
+
+    async fn request_pin_code(&self, device: &Device) -> Result<String, PairingRejected> {
+        let device = device.clone();
+        self.request(|reply| PairingRequest::RequestPinCode { device, reply })
+            .await
+    }
+
+    fn display_pin_code(&self, device: &Device, pin_code: &str) {
+        let device = device.clone();
+        let pin_code = pin_code.to_owned();
+        let _ = self
+            .sender
+            .unbounded_send(PairingRequest::DisplayPinCode { device, pin_code });
+    }
+
+    async fn authorize_service(&self, device: &Device, service: Uuid) -> Result<(), PairingRejected> {
+        let device = device.clone();
+        self.request(|reply| PairingRequest::AuthorizeService { device, service, reply })
+            .await
+    }
+}
@@ -0,0 +1,237 @@
+//! Advertisement filtering for [`Adapter::scan_with_filter`][crate::Adapter::scan_with_filter].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::ErrorKind;
+use crate::{AdvertisementData, AdvertisingDevice, DeviceId, Error, ManufacturerData, Result, Uuid};
+
+/// A custom criterion for [`ScanFilter::predicate`]
+pub type ScanPredicate = Arc<dyn Fn(&AdvertisingDevice) -> bool + Send + Sync>;
+
+/// Criteria an advertisement must satisfy to be returned by
+/// [`Adapter::scan_with_filter`][crate::Adapter::scan_with_filter].
+///
+/// An advertisement is returned only if it matches every criterion that is set. Criteria are applied by the platform
+/// Bluetooth stack where it supports them and in software otherwise.
+#[derive(Clone, Default)]
+pub struct ScanFilter {
+    /// Match advertisements including at least one of these GATT services. An empty list matches any advertisement.
+    pub services: Vec<Uuid>,
+    /// Match advertisements by their local name
+    pub local_name: Option<LocalNameFilter>,
+    /// Match advertisements including matching manufacturer specific data
+    pub manufacturer_data: Option<ManufacturerDataFilter>,
+    /// Match advertisements including matching service data
+    pub service_data: Option<ServiceDataFilter>,
+    /// Match advertisements received with a signal strength of at least this many dBm
+    pub min_rssi: Option<i16>,
+    /// Match only connectable advertisements
+    ///
+    /// On Linux, [`is_connectable`][AdvertisementData::is_connectable] is always `true`, so this has no effect.
+    pub connectable_only: bool,
+    /// Match advertisements for which this function returns `true`
+    pub predicate: Option<ScanPredicate>,
+}
+
+impl std::fmt::Debug for ScanFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScanFilter")
+            .field("services", &self.services)
+            .field("local_name", &self.local_name)
+            .field("manufacturer_data", &self.manufacturer_data)
+            .field("service_data", &self.service_data)
+            .field("min_rssi", &self.min_rssi)
+            .field("connectable_only", &self.connectable_only)
+            .field("predicate", &self.predicate.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl ScanFilter {
+    /// Creates a filter matching advertisements including at least one GATT service in `services`
+    pub fn with_services(services: &[Uuid]) -> Self {
+        ScanFilter {
+            services: services.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if `device` satisfies every criterion of this filter
+    pub fn matches(&self, device: &AdvertisingDevice) -> bool {
+        let adv_data = &device.adv_data;
+
+        (self.services.is_empty() || adv_data.services.iter().any(|x| self.services.contains(x)))
+            && self
+                .local_name
+                .as_ref()
+                .is_none_or(|filter| adv_data.local_name.as_deref().is_some_and(|x| filter.matches(x)))
+            && self.manufacturer_data.as_ref().is_none_or(|filter| {
+                adv_data
+                    .all_manufacturer_data
+                    .iter()
+                    .chain(&adv_data.manufacturer_data)
+                    .any(|x| filter.matches(x))
+            })
+            && self.service_data.as_ref().is_none_or(|filter| {
+                adv_data
+                    .service_data
+                    .get(&filter.uuid)
+                    .is_some_and(|x| masked_eq(&filter.data, filter.mask.as_deref(), x))
+            })
+            && self.min_rssi.is_none_or(|min| device.rssi.is_some_and(|x| x >= min))
+            && (!self.connectable_only || adv_data.is_connectable)
+            && self.predicate.as_ref().is_none_or(|predicate| predicate(device))
+    }
+}
+
+/// Options controlling how [`Adapter::scan_with_options`][crate::Adapter::scan_with_options] scans for advertisements
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ScanOptions {
+    /// Whether scan requests are sent to advertising devices
+    pub mode: ScanMode,
+    /// Which advertising packets are reported for a device which has already been seen
+    pub duplicates: DuplicatePolicy,
+    /// The transports scanned for devices
+    pub transport: ScanTransport,
+    /// Only report advertisements received with a path loss (advertised TX power minus RSSI) of at most this many dB.
+    /// Advertisements which do not include a TX power level are not reported.
+    pub max_pathloss: Option<u16>,
+}
+
+impl ScanOptions {
+    pub(crate) fn check_mode(&self, passive_supported: bool) -> Result<()> {
+        if self.mode == ScanMode::Passive && !passive_supported {
+            Err(Error::new(
+                ErrorKind::NotSupported,
+                None,
+                "passive scanning is not supported on this platform".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns `true` if `device` satisfies the [`max_pathloss`][Self::max_pathloss] of these options
+    pub(crate) fn matches(&self, device: &AdvertisingDevice) -> bool {
+        self.max_pathloss
+            .is_none_or(|max| match (device.adv_data.tx_power_level, device.rssi) {
+                (Some(tx_power), Some(rssi)) => i32::from(tx_power) - i32::from(rssi) <= i32::from(max),
+                _ => false,
+            })
+    }
+
+    /// Returns a predicate implementing [`DuplicatePolicy::ReportChanges`] in software for platforms which do not
+    /// support it natively. All other policies report every advertisement.
+    pub(crate) fn duplicate_filter(&self) -> impl FnMut(&AdvertisingDevice) -> bool + Send {
+        let report_changes = self.duplicates == DuplicatePolicy::ReportChanges;
+        let mut seen = HashMap::<DeviceId, AdvertisementData>::new();
+        move |device: &AdvertisingDevice| {
+            if !report_changes {
+                return true;
+            }
+
+            match seen.get(&device.device.id()) {
+                Some(adv_data) if *adv_data == device.adv_data => false,
+                _ => {
+                    seen.insert(device.device.id(), device.adv_data.clone());
+                    true
+                }
+            }
+        }
+    }
+}
+
+/// Whether a scan requests additional data from advertising devices
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ScanMode {
+    /// Send scan requests to scannable devices, so that their scan responses are included in the reported
+    /// advertisement data
+    #[default]
+    Active,
+    /// Only listen for advertising packets. This uses less power, but scan response data is not reported.
+    Passive,
+}
+
+/// Which advertising packets are reported for a device which has already been seen during a scan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DuplicatePolicy {
+    /// Use the default behavior of the platform Bluetooth stack
+    #[default]
+    PlatformDefault,
+    /// Report every advertising packet received
+    ReportAll,
+    /// Report an advertisement only if its data differs from the last one reported for the same device
+    ReportChanges,
+}
+
+/// The transports on which a scan discovers devices
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ScanTransport {
+    /// Only discover Bluetooth LE devices
+    #[default]
+    Le,
+    /// Discover both Bluetooth LE and BR/EDR devices, on platforms which support it
+    Dual,
+}
+
+/// Criterion matching the local name of an advertisement
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LocalNameFilter {
+    /// The local name must be equal to this string
+    Exact(String),
+    /// The local name must start with this string
+    Prefix(String),
+}
+
+impl LocalNameFilter {
+    /// Returns `true` if `name` satisfies this criterion
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            LocalNameFilter::Exact(x) => name == x,
+            LocalNameFilter::Prefix(x) => name.starts_with(x.as_str()),
+        }
+    }
+}
+
+/// Criterion matching the manufacturer specific data of an advertisement
+///
+/// The advertised data must start with [`data`][Self::data] in every bit set in [`mask`][Self::mask].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ManufacturerDataFilter {
+    /// Company identifier (defined [here](https://www.bluetooth.com/specifications/assigned-numbers/company-identifiers/))
+    pub company_id: u16,
+    /// The expected prefix of the manufacturer specific data
+    pub data: Vec<u8>,
+    /// Bits of `data` which must match. If `None` or shorter than `data`, the remaining bits must all match.
+    pub mask: Option<Vec<u8>>,
+}
+
+impl ManufacturerDataFilter {
+    /// Returns `true` if `data` satisfies this criterion
+    pub fn matches(&self, data: &ManufacturerData) -> bool {
+        data.company_id == self.company_id && masked_eq(&self.data, self.mask.as_deref(), &data.data)
+    }
+}
+
+/// Criterion matching the service data of an advertisement
+///
+/// The data advertised for [`uuid`][Self::uuid] must start with [`data`][Self::data] in every bit set in
+/// [`mask`][Self::mask].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServiceDataFilter {
+    /// The [`Uuid`] of the service the data is associated with
+    pub uuid: Uuid,
+    /// The expected prefix of the service data
+    pub data: Vec<u8>,
+    /// Bits of `data` which must match. If `None` or shorter than `data`, the remaining bits must all match.
+    pub mask: Option<Vec<u8>>,
+}
+
+fn masked_eq(pattern: &[u8], mask: Option<&[u8]>, value: &[u8]) -> bool {
+    value.len() >= pattern.len()
+        && pattern.iter().zip(value).enumerate().all(|(i, (pattern, value))| {
+            let mask = mask.and_then(|x| x.get(i)).copied().unwrap_or(0xff);
+            (pattern ^ value) & mask == 0
+        })
+}
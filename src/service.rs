@@ -6,12 +6,6 @@ pub struct Service(pub(crate) sys::service::ServiceImpl);
 
 impl Service {
     /// The [`Uuid`] identifying the type of this GATT service
-    ///
-    /// # Panics
-    ///
-    /// On Linux, this method will panic if there is a current Tokio runtime and it is single-threaded, if there is no
-    /// current Tokio runtime and creating one fails, or if the underlying [`Service::uuid_async()`] method
-    /// fails.
     #[inline]
     pub fn uuid(&self) -> Uuid {
         self.0.uuid()
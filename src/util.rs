@@ -1,6 +1,12 @@
 #![allow(unused)] // used depending on the target.
 
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::mem::ManuallyDrop;
+use std::pin::Pin;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
 
 pub struct ScopeGuard<F: FnOnce()> {
     dropfn: ManuallyDrop<F>,
@@ -19,3 +25,119 @@ pub fn defer<F: FnOnce()>(dropfn: F) -> ScopeGuard<F> {
         dropfn: ManuallyDrop::new(dropfn),
     }
 }
+
+/// Completes after `duration` has elapsed.
+///
+/// All timers are driven by a single helper thread, so that they work with any async runtime. Dropping the returned
+/// future cancels the timer.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        deadline: Instant::now().checked_add(duration),
+        id: None,
+    }
+}
+
+/// Future returned by [`sleep`]
+pub struct Sleep {
+    /// `None` if the deadline is too far in the future to be represented
+    deadline: Option<Instant>,
+    /// Set while the timer is registered with the timer thread
+    id: Option<u64>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let Some(deadline) = this.deadline else {
+            return Poll::Pending;
+        };
+        if Instant::now() >= deadline {
+            this.cancel();
+            return Poll::Ready(());
+        }
+
+        let timer = Timer::get();
+        let mut state = timer.state.lock().unwrap();
+        let id = *this.id.get_or_insert_with(|| {
+            state.next_id += 1;
+            state.next_id
+        });
+        state.timers.insert((deadline, id), cx.waker().clone());
+        // Wake the timer thread if this is now the earliest deadline
+        if state.timers.keys().next() == Some(&(deadline, id)) {
+            timer.condvar.notify_one();
+        }
+        Poll::Pending
+    }
+}
+
+impl Sleep {
+    fn cancel(&mut self) {
+        if let (Some(deadline), Some(id)) = (self.deadline, self.id.take()) {
+            Timer::get().state.lock().unwrap().timers.remove(&(deadline, id));
+        }
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// The helper thread shared by all [`Sleep`] futures
+struct Timer {
+    state: Mutex<TimerState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct TimerState {
+    next_id: u64,
+    /// Pending timers ordered by deadline
+    timers: BTreeMap<(Instant, u64), Waker>,
+}
+
+impl Timer {
+    fn get() -> &'static Timer {
+        static TIMER: OnceLock<Timer> = OnceLock::new();
+        TIMER.get_or_init(|| {
+            std::thread::Builder::new()
+                .name("bluest-timer".to_string())
+                .spawn(|| Timer::get().run())
+                .expect("failed to spawn timer thread");
+            Timer {
+                state: Mutex::new(TimerState::default()),
+                condvar: Condvar::new(),
+            }
+        })
+    }
+
+    fn run(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let mut expired = Vec::new();
+            while let Some(entry) = state.timers.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+                expired.push(entry.remove());
+            }
+
+            if !expired.is_empty() {
+                drop(state);
+                expired.into_iter().for_each(Waker::wake);
+                state = self.state.lock().unwrap();
+                continue;
+            }
+
+            state = match state.timers.first_key_value() {
+                Some((&(deadline, _), _)) => self.condvar.wait_timeout(state, deadline - now).unwrap().0,
+                None => self.condvar.wait(state).unwrap(),
+            };
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures_core::Stream;
 use futures_lite::{stream, StreamExt};
 use tracing::{debug, error, trace, warn};
-use windows::core::HSTRING;
+use windows::core::{ComInterface, HSTRING};
 use windows::Devices::Bluetooth::Advertisement::{
     BluetoothLEAdvertisement, BluetoothLEAdvertisementDataSection, BluetoothLEAdvertisementFilter,
     BluetoothLEAdvertisementReceivedEventArgs, BluetoothLEAdvertisementType, BluetoothLEAdvertisementWatcher,
@@ -15,15 +16,15 @@ use windows::Devices::Bluetooth::{BluetoothAdapter, BluetoothConnectionStatus, B
 use windows::Devices::Enumeration::{DeviceInformation, DeviceInformationKind};
 use windows::Devices::Radios::{Radio, RadioState};
 use windows::Foundation::Collections::{IIterable, IVector};
-use windows::Foundation::TypedEventHandler;
+use windows::Foundation::{IReference, PropertyValue, TypedEventHandler};
 use windows::Storage::Streams::DataReader;
 
 use super::types::StringVec;
 use crate::error::{Error, ErrorKind};
 use crate::util::defer;
 use crate::{
-    AdapterEvent, AdvertisementData, AdvertisingDevice, BluetoothUuidExt, ConnectionEvent, Device, DeviceId,
-    ManufacturerData, Result, Uuid,
+    AdapterEvent, AdvertisementData, AdvertisingDevice, BluetoothUuidExt, ConnectionEvent, Device, DeviceEvent,
+    DeviceId, DisconnectReason, LocalNameFilter, ManufacturerData, Result, ScanFilter, ScanMode, ScanOptions, Uuid,
 };
 
 /// The system's Bluetooth adapter interface.
@@ -61,6 +62,104 @@ impl AdapterImpl {
         Some(AdapterImpl { inner: adapter })
     }
 
+    /// Creates interfaces to all Bluetooth adapters of the system
+    pub async fn all() -> Result<Vec<Self>> {
+        let selector = BluetoothAdapter::GetDeviceSelector()?;
+        let devices = DeviceInformation::FindAllAsyncAqsFilter(&selector)?.await?;
+        let mut adapters = Vec::new();
+        for device in devices {
+            let inner = BluetoothAdapter::FromIdAsync(&device.Id()?)?.await?;
+            adapters.push(AdapterImpl { inner });
+        }
+        Ok(adapters)
+    }
+
+    /// Creates an interface to the Bluetooth adapter with the given name
+    pub async fn by_name(name: &str) -> Result<Self> {
+        for adapter in Self::all().await? {
+            if adapter.name()? == name {
+                return Ok(adapter);
+            }
+        }
+        Err(Error::new(
+            ErrorKind::NotFound,
+            None,
+            format!("no adapter named {name}"),
+        ))
+    }
+
+    /// The system name of this adapter, which uniquely identifies it
+    pub fn name(&self) -> Result<String> {
+        Ok(self.inner.DeviceId()?.to_string_lossy())
+    }
+
+    /// The Bluetooth address of this adapter, most significant byte first
+    pub async fn address(&self) -> Result<[u8; 6]> {
+        let address = self.inner.BluetoothAddress()?.to_be_bytes();
+        Ok(address[2..].try_into().unwrap())
+    }
+
+    /// The human readable name of this adapter, which is shown to remote devices
+    pub async fn alias(&self) -> Result<String> {
+        let radio = self.inner.GetRadioAsync()?.await?;
+        Ok(radio.Name()?.to_string_lossy())
+    }
+
+    /// Whether this adapter is powered on
+    pub async fn is_powered(&self) -> Result<bool> {
+        let radio = self.inner.GetRadioAsync()?.await?;
+        Ok(radio.State()? == RadioState::On)
+    }
+
+    /// Whether this adapter is discoverable by remote devices
+    ///
+    /// Not supported on Windows.
+    pub async fn is_discoverable(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Whether this adapter accepts pairing requests from remote devices
+    ///
+    /// Not supported on Windows.
+    pub async fn is_pairable(&self) -> Result<bool> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Powers this adapter on or off
+    ///
+    /// Not supported on Windows.
+    pub async fn set_powered(&self, _powered: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Makes this adapter discoverable by remote devices, or stops it from being discoverable
+    ///
+    /// Not supported on Windows.
+    pub async fn set_discoverable(&self, _discoverable: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Sets how long this adapter stays discoverable after `set_discoverable` is called
+    ///
+    /// Not supported on Windows.
+    pub async fn set_discoverable_timeout(&self, _timeout: Duration) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Allows or disallows pairing requests from remote devices
+    ///
+    /// Not supported on Windows.
+    pub async fn set_pairable(&self, _pairable: bool) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// Sets how long this adapter stays pairable after `set_pairable` is called
+    ///
+    /// Not supported on Windows.
+    pub async fn set_pairable_timeout(&self, _timeout: Duration) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// A stream of [`AdapterEvent`] which allows the application to identify when the adapter is enabled or disabled.
     pub async fn events(&self) -> Result<impl Stream<Item = Result<AdapterEvent>> + Send + Unpin + '_> {
         let (mut sender, receiver) = futures_channel::mpsc::channel(16);
@@ -122,24 +221,19 @@ impl AdapterImpl {
     /// Finds all connected Bluetooth LE devices
     pub async fn connected_devices(&self) -> Result<Vec<Device>> {
         let aqsfilter = BluetoothLEDevice::GetDeviceSelectorFromConnectionStatus(BluetoothConnectionStatus::Connected)?;
+        find_devices(&aqsfilter).await
+    }
 
-        let op = DeviceInformation::FindAllAsyncWithKindAqsFilterAndAdditionalProperties(
-            &aqsfilter,
-            None,
-            DeviceInformationKind::AssociationEndpoint,
-        )?;
-        let devices = op.await?;
-        let device_ids: Vec<HSTRING> = devices
-            .into_iter()
-            .map(|x| x.Id())
-            .collect::<windows::core::Result<_>>()?;
-
-        let mut res = Vec::with_capacity(device_ids.len());
-        for id in device_ids {
-            res.push(Device::from_id(&id).await?);
-        }
+    /// Finds all Bluetooth LE devices which are paired with the system
+    pub async fn paired_devices(&self) -> Result<Vec<Device>> {
+        let aqsfilter = BluetoothLEDevice::GetDeviceSelectorFromPairingState(true)?;
+        find_devices(&aqsfilter).await
+    }
 
-        Ok(res)
+    /// Finds all Bluetooth LE devices known to the system
+    pub async fn known_devices(&self) -> Result<Vec<Device>> {
+        let aqsfilter = BluetoothLEDevice::GetDeviceSelector()?;
+        find_devices(&aqsfilter).await
     }
 
     /// Finds all connected devices providing any service in `services`
@@ -248,6 +342,35 @@ impl AdapterImpl {
         &'a self,
         services: &'a [Uuid],
     ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + 'a> {
+        self.scan_with_filter(ScanFilter::with_services(services)).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter`.
+    ///
+    /// The services, exact local name and minimum RSSI of `filter` are applied by the advertisement watcher. All
+    /// criteria are additionally checked in software.
+    pub async fn scan_with_filter(
+        &self,
+        filter: ScanFilter,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        self.scan_with_options(filter, ScanOptions::default()).await
+    }
+
+    /// Starts scanning for Bluetooth advertising packets matching `filter` using the given `options`.
+    ///
+    /// The advertisement watcher reports every packet received, so
+    /// [`DuplicatePolicy::ReportChanges`][crate::DuplicatePolicy::ReportChanges] and the maximum path loss are applied
+    /// in software. Only Bluetooth LE devices are discovered.
+    pub async fn scan_with_options(
+        &self,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<impl Stream<Item = AdvertisingDevice> + Send + Unpin + '_> {
+        let scanning_mode = match options.mode {
+            ScanMode::Active => BluetoothLEScanningMode::Active,
+            ScanMode::Passive => BluetoothLEScanningMode::Passive,
+        };
+
         let (sender, receiver) = futures_channel::mpsc::channel(16);
         let sender = Arc::new(std::sync::Mutex::new(sender));
 
@@ -281,29 +404,45 @@ impl AdapterImpl {
             },
         );
 
+        let local_name = match &filter.local_name {
+            Some(LocalNameFilter::Exact(name)) => Some(HSTRING::from(name.as_str())),
+            _ => None,
+        };
+
         let build_watcher = |uuid: Option<Uuid>| {
             let watcher = BluetoothLEAdvertisementWatcher::new()?;
-            watcher.SetScanningMode(BluetoothLEScanningMode::Active)?;
+            watcher.SetScanningMode(scanning_mode)?;
             watcher.SetAllowExtendedAdvertisements(true)?;
             watcher.Received(&received_handler)?;
             watcher.Stopped(&stopped_handler)?;
 
-            if let Some(uuid) = uuid {
+            if uuid.is_some() || local_name.is_some() {
                 let advertisement = BluetoothLEAdvertisement::new()?;
-                let service_uuids = advertisement.ServiceUuids()?;
-                service_uuids.Append(windows::core::GUID::from_u128(uuid.as_u128()))?;
+                if let Some(uuid) = uuid {
+                    let service_uuids = advertisement.ServiceUuids()?;
+                    service_uuids.Append(windows::core::GUID::from_u128(uuid.as_u128()))?;
+                }
+                if let Some(local_name) = &local_name {
+                    advertisement.SetLocalName(local_name)?;
+                }
                 let advertisement_filter = BluetoothLEAdvertisementFilter::new()?;
                 advertisement_filter.SetAdvertisement(&advertisement)?;
                 watcher.SetAdvertisementFilter(&advertisement_filter)?;
             }
 
+            if let Some(min_rssi) = filter.min_rssi {
+                let threshold: IReference<i16> = PropertyValue::CreateInt16(min_rssi)?.cast()?;
+                watcher.SignalStrengthFilter()?.SetInRangeThresholdInDBm(&threshold)?;
+            }
+
             Ok::<_, windows::core::Error>(watcher)
         };
 
-        let watchers = if services.is_empty() {
+        let watchers = if filter.services.is_empty() {
             vec![build_watcher(None)?]
         } else {
-            services
+            filter
+                .services
                 .iter()
                 .map(|uuid| build_watcher(Some(*uuid)))
                 .collect::<Result<_, _>>()?
@@ -349,7 +488,9 @@ impl AdapterImpl {
                     }
                 })
             })
-            .filter_map(|x| x))
+            .filter_map(|x| x)
+            .filter(move |x| filter.matches(x) && options.matches(x))
+            .filter(options.duplicate_filter()))
     }
 
     /// Finds Bluetooth devices providing any service in `services`.
@@ -426,12 +567,42 @@ impl AdapterImpl {
             ConnectionEvent::from(x)
         }))
     }
+
+    /// Monitors all devices known to the adapter for changes.
+    ///
+    /// Not supported on Windows.
+    pub async fn device_events(&self) -> Result<impl Stream<Item = Result<DeviceEvent>> + Send + Unpin + '_> {
+        Err::<stream::Empty<_>, _>(ErrorKind::NotSupported.into())
+    }
+}
+
+/// Finds all Bluetooth LE devices matching the AQS filter `aqsfilter`
+async fn find_devices(aqsfilter: &HSTRING) -> Result<Vec<Device>> {
+    let op = DeviceInformation::FindAllAsyncWithKindAqsFilterAndAdditionalProperties(
+        aqsfilter,
+        None,
+        DeviceInformationKind::AssociationEndpoint,
+    )?;
+    let devices = op.await?;
+    let device_ids: Vec<HSTRING> = devices
+        .into_iter()
+        .map(|x| x.Id())
+        .collect::<windows::core::Result<_>>()?;
+
+    let mut res = Vec::with_capacity(device_ids.len());
+    for id in device_ids {
+        res.push(Device::from_id(&id).await?);
+    }
+
+    Ok(res)
 }
 
 impl From<BluetoothConnectionStatus> for ConnectionEvent {
     fn from(value: BluetoothConnectionStatus) -> Self {
         match value {
-            BluetoothConnectionStatus::Disconnected => ConnectionEvent::Disconnected,
+            BluetoothConnectionStatus::Disconnected => ConnectionEvent::Disconnected {
+                reason: DisconnectReason::Unknown,
+            },
             _ => ConnectionEvent::Connected,
         }
     }
@@ -454,40 +625,46 @@ impl From<BluetoothLEAdvertisementReceivedEventArgs> for AdvertisementData {
     fn from(event_args: BluetoothLEAdvertisementReceivedEventArgs) -> Self {
         let is_connectable = event_args.IsConnectable().unwrap_or(false);
         let tx_power_level = event_args.TransmitPowerLevelInDBm().ok().and_then(|x| x.Value().ok());
-        let (local_name, manufacturer_data, services, service_data) = if let Ok(adv) = event_args.Advertisement() {
-            let local_name = adv
-                .LocalName()
-                .ok()
-                .and_then(|x| (!x.is_empty()).then(|| x.to_string_lossy()));
-            let manufacturer_data = adv
-                .ManufacturerData()
-                .and_then(|x| x.GetAt(0))
-                .and_then(|x| x.try_into())
-                .ok();
-
-            let services = adv
-                .ServiceUuids()
-                .map(|x| x.into_iter().map(|x| Uuid::from_u128(x.to_u128())).collect())
-                .unwrap_or_default();
-
-            let service_data = if let Ok(data_sections) = adv.DataSections() {
-                to_service_data(&data_sections).unwrap_or_default()
+        let (local_name, all_manufacturer_data, services, service_data, flags) =
+            if let Ok(adv) = event_args.Advertisement() {
+                let local_name = adv
+                    .LocalName()
+                    .ok()
+                    .and_then(|x| (!x.is_empty()).then(|| x.to_string_lossy()));
+                let all_manufacturer_data: Vec<ManufacturerData> = adv
+                    .ManufacturerData()
+                    .map(|x| x.into_iter().filter_map(|x| x.try_into().ok()).collect())
+                    .unwrap_or_default();
+
+                let services = adv
+                    .ServiceUuids()
+                    .map(|x| x.into_iter().map(|x| Uuid::from_u128(x.to_u128())).collect())
+                    .unwrap_or_default();
+
+                let service_data = if let Ok(data_sections) = adv.DataSections() {
+                    to_service_data(&data_sections).unwrap_or_default()
+                } else {
+                    Default::default()
+                };
+
+                // Only the low byte of the flags is defined by the Core Specification Supplement
+                let flags = adv.Flags().and_then(|x| x.Value()).ok().map(|x| x.0 as u8);
+
+                (local_name, all_manufacturer_data, services, service_data, flags)
             } else {
-                Default::default()
+                (None, Vec::new(), Vec::new(), HashMap::new(), None)
             };
 
-            (local_name, manufacturer_data, services, service_data)
-        } else {
-            (None, None, Vec::new(), HashMap::new())
-        };
-
         AdvertisementData {
             local_name,
-            manufacturer_data,
+            manufacturer_data: all_manufacturer_data.first().cloned(),
+            all_manufacturer_data,
             services,
             tx_power_level,
+            flags,
             is_connectable,
             service_data,
+            ..Default::default()
         }
     }
 }
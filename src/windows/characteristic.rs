@@ -149,6 +149,11 @@ impl CharacteristicImpl {
     ///
     /// Returns a stream of values for the characteristic sent from the device.
     pub async fn notify(&self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + '_> {
+        self.clone().notify_owned().await
+    }
+
+    /// Like [`notify`][Self::notify], but the returned stream owns the characteristic.
+    pub async fn notify_owned(self) -> Result<impl Stream<Item = Result<Vec<u8>>> + Send + Unpin + 'static> {
         let props = self.properties().await?;
         let value = if props.notify {
             GattClientCharacteristicConfigurationDescriptorValue::Notify
@@ -186,8 +191,9 @@ impl CharacteristicImpl {
             },
         ))?;
 
+        let inner = self.inner.clone();
         let guard = defer(move || {
-            if let Err(err) = self.inner.RemoveValueChanged(token) {
+            if let Err(err) = inner.RemoveValueChanged(token) {
                 warn!("Error removing value change event handler: {:?}", err);
             }
         });
@@ -1,23 +1,28 @@
 use std::pin::pin;
+use std::sync::Mutex;
 
 use futures_channel::mpsc;
 use futures_core::Stream;
 use futures_lite::{future, StreamExt};
 use tracing::error;
 use windows::core::{GUID, HSTRING};
+use windows::Devices::Bluetooth::GenericAttributeProfile::GattSession;
 use windows::Devices::Bluetooth::{
     BluetoothAddressType, BluetoothCacheMode, BluetoothConnectionStatus, BluetoothLEDevice,
 };
-use windows::Devices::Enumeration::{DevicePairingKinds, DevicePairingRequestedEventArgs};
+use windows::Devices::Enumeration::{
+    DevicePairingKinds, DevicePairingProtectionLevel, DevicePairingRequestedEventArgs, DevicePairingResult,
+    DevicePairingResultStatus,
+};
 use windows::Foundation::TypedEventHandler;
 
 use super::error::{check_communication_status, check_pairing_status, check_unpairing_status};
 use super::l2cap_channel::{L2capChannelReader, L2capChannelWriter};
 use crate::device::ServicesChanged;
 use crate::error::ErrorKind;
-use crate::pairing::{IoCapability, PairingAgent, Passkey};
+use crate::pairing::{IoCapability, PairingAgent, PairingMethod, PairingResult, Passkey};
 use crate::util::defer;
-use crate::{Device, DeviceId, Error, Result, Service, Uuid};
+use crate::{Device, DeviceId, DeviceProperty, Error, Result, SecurityLevel, Service, Uuid};
 
 /// A Bluetooth LE device
 #[derive(Clone)]
@@ -94,6 +99,10 @@ impl DeviceImpl {
         self.name()
     }
 
+    pub async fn alias(&self) -> Result<String> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     /// The connection status for this device
     pub async fn is_connected(&self) -> bool {
         self.inner.ConnectionStatus() == Ok(BluetoothConnectionStatus::Connected)
@@ -111,14 +120,14 @@ impl DeviceImpl {
     /// Attempt to pair this device using the system default pairing UI
     ///
     /// This will fail unless it is called from a UWP application.
-    pub async fn pair(&self) -> Result<()> {
+    pub async fn pair(&self) -> Result<PairingResult> {
         let op = self.inner.DeviceInformation()?.Pairing()?.PairAsync()?;
         let res = op.await?;
-        check_pairing_status(res.Status()?)
+        pairing_result(&res, None)
     }
 
     /// Attempt to pair this device using the system default pairing UI
-    pub async fn pair_with_agent<T: PairingAgent>(&self, agent: &T) -> Result<()> {
+    pub async fn pair_with_agent<T: PairingAgent>(&self, agent: &T) -> Result<PairingResult> {
         let pairing_kinds_supported = match agent.io_capability() {
             IoCapability::DisplayOnly => DevicePairingKinds::DisplayPin,
             IoCapability::DisplayYesNo => {
@@ -149,17 +158,33 @@ impl DeviceImpl {
         let op = custom.PairAsync(pairing_kinds_supported)?;
 
         let device = Device(self.clone());
+        let method = &Mutex::new(None);
         let pairing_fut = pin!(async move {
             while let Some((event_args, deferral)) = rx.next().await {
-                match event_args.PairingKind()? {
+                let kind = event_args.PairingKind()?;
+                *method.lock().unwrap() = match kind {
+                    DevicePairingKinds::ConfirmOnly => Some(PairingMethod::JustWorks),
+                    DevicePairingKinds::DisplayPin | DevicePairingKinds::ProvidePin => {
+                        Some(PairingMethod::PasskeyEntry)
+                    }
+                    DevicePairingKinds::ConfirmPinMatch => Some(PairingMethod::NumericComparison),
+                    _ => None,
+                };
+
+                match kind {
                     DevicePairingKinds::ConfirmOnly => {
                         if agent.confirm(&device).await.is_ok() {
                             event_args.Accept()?;
                         }
                     }
                     DevicePairingKinds::DisplayPin => {
-                        if let Ok(passkey) = event_args.Pin()?.to_string_lossy().parse::<Passkey>() {
-                            agent.display_passkey(&device, passkey);
+                        let pin = event_args.Pin()?.to_string_lossy();
+                        match pin.parse::<Passkey>() {
+                            Ok(passkey) if pin.len() == 6 => agent.display_passkey(&device, passkey),
+                            _ => {
+                                *method.lock().unwrap() = Some(PairingMethod::LegacyPin);
+                                agent.display_pin_code(&device, &pin)
+                            }
                         }
                     }
                     DevicePairingKinds::ProvidePin => {
@@ -184,8 +209,8 @@ impl DeviceImpl {
         });
 
         let op = async move {
-            let res = op.await;
-            check_pairing_status(res?.Status()?)
+            let res = op.await?;
+            pairing_result(&res, *method.lock().unwrap())
         };
         let pairing_fut = async move {
             pairing_fut.await.and_then(|_| {
@@ -206,6 +231,20 @@ impl DeviceImpl {
         check_unpairing_status(res.Status()?)
     }
 
+    /// The protection level this device was paired with, or [`SecurityLevel::Low`] if it is not paired
+    pub async fn security_level(&self) -> Result<SecurityLevel> {
+        let pairing = self.inner.DeviceInformation()?.Pairing()?;
+        if !pairing.IsPaired()? {
+            return Ok(SecurityLevel::Low);
+        }
+        Ok(security_level(pairing.ProtectionLevel()?).unwrap_or(SecurityLevel::Low))
+    }
+
+    /// Whether this device is connected and paired, in which case Windows encrypts the connection
+    pub async fn is_encrypted(&self) -> Result<bool> {
+        Ok(self.is_connected().await && self.security_level().await? > SecurityLevel::Low)
+    }
+
     /// Discover the primary services of this device.
     pub async fn discover_services(&self) -> Result<Vec<Service>> {
         let res = self
@@ -266,6 +305,50 @@ impl DeviceImpl {
         }))
     }
 
+    /// Monitors the device for changes of its properties.
+    ///
+    /// Only name and MTU changes are reported.
+    pub async fn property_changes(&self) -> Result<impl Stream<Item = DeviceProperty> + Send + Unpin + '_> {
+        let session = GattSession::FromDeviceIdAsync(&self.inner.BluetoothDeviceId()?)?.await?;
+        let (mut name_sender, receiver) = futures_channel::mpsc::channel(16);
+        let mut mtu_sender = name_sender.clone();
+
+        let token = self
+            .inner
+            .NameChanged(&TypedEventHandler::new(move |device: &Option<BluetoothLEDevice>, _| {
+                if let Some(device) = device {
+                    if let Err(err) = name_sender.try_send(DeviceProperty::Name(device.Name()?.to_string_lossy())) {
+                        error!("Error sending name change: {:?}", err);
+                    }
+                }
+                Ok(())
+            }))?;
+        let name_guard = defer(move || {
+            if let Err(err) = self.inner.RemoveNameChanged(token) {
+                error!("Error removing name changed handler: {:?}", err);
+            }
+        });
+
+        let token = session.MaxPduSizeChanged(&TypedEventHandler::new(move |session: &Option<GattSession>, _| {
+            if let Some(session) = session {
+                if let Err(err) = mtu_sender.try_send(DeviceProperty::Mtu(session.MaxPduSize()?.into())) {
+                    error!("Error sending MTU change: {:?}", err);
+                }
+            }
+            Ok(())
+        }))?;
+        let mtu_guard = defer(move || {
+            if let Err(err) = session.RemoveMaxPduSizeChanged(token) {
+                error!("Error removing MTU changed handler: {:?}", err);
+            }
+        });
+
+        Ok(receiver.map(move |x| {
+            let _guards = (&name_guard, &mtu_guard);
+            x
+        }))
+    }
+
     /// Get the current signal strength from the device in dBm.
     ///
     /// Returns [ErrorKind::NotSupported].
@@ -273,6 +356,11 @@ impl DeviceImpl {
         Err(ErrorKind::NotSupported.into())
     }
 
+    /// Returns [ErrorKind::NotSupported].
+    pub async fn refresh_rssi(&self) -> Result<i16> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     #[cfg(feature = "l2cap")]
     pub async fn open_l2cap_channel(
         &self,
@@ -291,3 +379,25 @@ impl ServicesChangedImpl {
         true
     }
 }
+
+fn pairing_result(res: &DevicePairingResult, method: Option<PairingMethod>) -> Result<PairingResult> {
+    let status = res.Status()?;
+    check_pairing_status(status)?;
+    Ok(PairingResult {
+        // Windows always stores the keys of paired devices
+        bonded: true,
+        method: (status == DevicePairingResultStatus::Paired)
+            .then_some(method)
+            .flatten(),
+        security_level: security_level(res.ProtectionLevelUsed()?),
+    })
+}
+
+fn security_level(level: DevicePairingProtectionLevel) -> Option<SecurityLevel> {
+    match level {
+        DevicePairingProtectionLevel::None => Some(SecurityLevel::Low),
+        DevicePairingProtectionLevel::Encryption => Some(SecurityLevel::Medium),
+        DevicePairingProtectionLevel::EncryptionAndAuthentication => Some(SecurityLevel::High),
+        _ => None,
+    }
+}
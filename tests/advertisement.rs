@@ -0,0 +1,136 @@
+use bluest::error::ErrorKind;
+use bluest::{btuuid, AdvertisementData, BluetoothUuidExt, ManufacturerData, Uuid};
+
+#[test]
+fn parses_ad_structures() {
+    let data = [
+        0x02, 0x01, 0x06, // flags
+        0x05, 0x03, 0x0f, 0x18, 0x0a, 0x18, // complete list of 16-bit UUIDs
+        0x05, 0x05, 0x78, 0x56, 0x34, 0x12, // complete list of 32-bit UUIDs
+        0x04, 0x08, b'S', b'e', b'n', // shortened local name
+        0x07, 0x09, b'S', b'e', b'n', b's', b'o', b'r', // complete local name
+        0x02, 0x0a, 0xf4, // tx power level
+        0x03, 0x14, 0x0d, 0x18, // 16-bit service solicitation UUIDs
+        0x04, 0x16, 0x0f, 0x18, 0x64, // 16-bit service data
+        0x03, 0x19, 0xc1, 0x03, // appearance
+        0x0b, 0x24, 0x17, b'/', b'/', b'a', b'b', b'c', b'.', b'x', b'y', b'z', // URI
+        0x02, 0x27, 0x01, // LE supported features
+        0x05, 0xff, 0x4c, 0x00, 0x01, 0x02, // manufacturer specific data
+        0x03, 0x3d, 0xaa, 0xbb, // unknown AD type
+        0x00, 0x00, 0x00, // padding
+    ];
+
+    let adv = AdvertisementData::from_raw(&data).unwrap();
+    assert_eq!(adv.flags, Some(0x06));
+    assert_eq!(
+        adv.services,
+        vec![
+            btuuid::services::BATTERY,
+            btuuid::services::DEVICE_INFORMATION,
+            Uuid::from_u32(0x12345678)
+        ]
+    );
+    assert_eq!(adv.local_name.as_deref(), Some("Sensor"));
+    assert_eq!(adv.shortened_local_name.as_deref(), Some("Sen"));
+    assert_eq!(adv.tx_power_level, Some(-12));
+    assert_eq!(adv.solicited_services, vec![btuuid::services::HEART_RATE]);
+    assert_eq!(adv.service_data.get(&btuuid::services::BATTERY), Some(&vec![0x64]));
+    assert_eq!(adv.appearance, Some(0x03c1));
+    assert_eq!(adv.uri.as_deref(), Some("https://abc.xyz"));
+    assert_eq!(adv.le_supported_features, Some(vec![0x01]));
+    assert_eq!(
+        adv.manufacturer_data,
+        Some(ManufacturerData {
+            company_id: 0x004c,
+            data: vec![0x01, 0x02]
+        })
+    );
+    assert_eq!(adv.unknown_data, vec![(0x3d, vec![0xaa, 0xbb])]);
+    assert!(!adv.is_connectable);
+}
+
+#[test]
+fn parses_128_bit_uuids() {
+    let uuid = Uuid::from_u128(0x0102030405060708090a0b0c0d0e0f10);
+    let mut le = uuid.as_bytes().to_vec();
+    le.reverse();
+
+    let mut data = vec![0x11, 0x07];
+    data.extend_from_slice(&le);
+    data.extend_from_slice(&[0x13, 0x21]);
+    data.extend_from_slice(&le);
+    data.extend_from_slice(&[0xde, 0xad]);
+
+    let adv = AdvertisementData::from_raw(&data).unwrap();
+    assert_eq!(adv.services, vec![uuid]);
+    assert_eq!(adv.service_data.get(&uuid), Some(&vec![0xde, 0xad]));
+}
+
+#[test]
+fn rejects_malformed_data() {
+    let truncated = [0x05, 0x03, 0x0f, 0x18];
+    let err = AdvertisementData::from_raw(&truncated).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+
+    let odd_uuid_list = [0x04, 0x03, 0x0f, 0x18, 0x0a];
+    let err = AdvertisementData::from_raw(&odd_uuid_list).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+
+    let short_manufacturer_data = [0x02, 0xff, 0x4c];
+    let err = AdvertisementData::from_raw(&short_manufacturer_data).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+}
+
+#[test]
+fn merges_scan_response() {
+    let mut adv = AdvertisementData::from_raw(&[
+        0x02, 0x01, 0x06, // flags
+        0x03, 0x03, 0x0f, 0x18, // complete list of 16-bit UUIDs
+        0x04, 0x08, b'S', b'e', b'n', // shortened local name
+    ])
+    .unwrap();
+    let scan_response = AdvertisementData::from_raw(&[
+        0x03, 0x03, 0x0a, 0x18, // complete list of 16-bit UUIDs
+        0x07, 0x09, b'S', b'e', b'n', b's', b'o', b'r', // complete local name
+        0x02, 0x0a, 0x00, // tx power level
+    ])
+    .unwrap();
+
+    adv.merge(scan_response);
+    assert_eq!(adv.flags, Some(0x06));
+    assert_eq!(
+        adv.services,
+        vec![btuuid::services::BATTERY, btuuid::services::DEVICE_INFORMATION]
+    );
+    assert_eq!(adv.local_name.as_deref(), Some("Sensor"));
+    assert_eq!(adv.shortened_local_name.as_deref(), Some("Sen"));
+    assert_eq!(adv.tx_power_level, Some(0));
+
+    // A complete name is not replaced by a shortened one
+    adv.merge(AdvertisementData::from_raw(&[0x03, 0x08, b'S', b'e']).unwrap());
+    assert_eq!(adv.local_name.as_deref(), Some("Sensor"));
+}
+
+#[test]
+fn keeps_all_manufacturer_data() {
+    let mut adv = AdvertisementData::from_raw(&[
+        0x04, 0xff, 0x4c, 0x00, 0x01, // manufacturer specific data
+        0x04, 0xff, 0x59, 0x00, 0x02, // manufacturer specific data
+    ])
+    .unwrap();
+
+    let apple = ManufacturerData {
+        company_id: 0x004c,
+        data: vec![0x01],
+    };
+    let nordic = ManufacturerData {
+        company_id: 0x0059,
+        data: vec![0x02],
+    };
+    assert_eq!(adv.manufacturer_data, Some(apple.clone()));
+    assert_eq!(adv.all_manufacturer_data, vec![apple.clone(), nordic.clone()]);
+
+    adv.merge(AdvertisementData::from_raw(&[0x04, 0xff, 0x59, 0x00, 0x02]).unwrap());
+    assert_eq!(adv.manufacturer_data, Some(apple.clone()));
+    assert_eq!(adv.all_manufacturer_data, vec![apple, nordic]);
+}
@@ -1,5 +1,7 @@
 #![allow(clippy::let_unit_value)]
 
+use std::time::Duration;
+
 use bluest::*;
 use futures_lite::StreamExt;
 
@@ -12,13 +14,44 @@ async fn check_adapter_apis(adapter: Adapter) -> Result<Device> {
     let _event: Option<Result<AdapterEvent>> = assert_send(events?.next()).await;
     let _available: Result<()> = assert_send(adapter.wait_available()).await;
 
+    let _adapters: Result<Vec<Adapter>> = assert_send(Adapter::all()).await;
+    let _adapter: Result<Adapter> = assert_send(Adapter::by_name("hci0")).await;
+    let _adapter: Result<Adapter> = assert_send(Adapter::by_address([0; 6])).await;
+    let _name: Result<String> = adapter.name();
+    let _address: Result<[u8; 6]> = assert_send(adapter.address()).await;
+    let _alias: Result<String> = assert_send(adapter.alias()).await;
+    let _powered: Result<bool> = assert_send(adapter.is_powered()).await;
+    let _discoverable: Result<bool> = assert_send(adapter.is_discoverable()).await;
+    let _pairable: Result<bool> = assert_send(adapter.is_pairable()).await;
+    let _res: Result<()> = assert_send(adapter.wait_available_timeout(Duration::from_secs(1))).await;
+    let _res: Result<()> = assert_send(adapter.set_powered(true)).await;
+    let _res: Result<()> = assert_send(adapter.set_discoverable(true)).await;
+    let _res: Result<()> = assert_send(adapter.set_discoverable_timeout(Duration::from_secs(60))).await;
+    let _res: Result<()> = assert_send(adapter.set_pairable(true)).await;
+    let _res: Result<()> = assert_send(adapter.set_pairable_timeout(Duration::ZERO)).await;
+
     let _devices: Result<Vec<Device>> = assert_send(adapter.connected_devices()).await;
+    let _devices: Result<Vec<Device>> = assert_send(adapter.paired_devices()).await;
+    let _devices: Result<Vec<Device>> = assert_send(adapter.known_devices()).await;
     let devices: Result<Vec<Device>> =
         assert_send(adapter.connected_devices_with_services(&[btuuid::services::GENERIC_ACCESS])).await;
 
     let scan: Result<_> = assert_send(adapter.scan(&[btuuid::services::GENERIC_ACCESS])).await;
     let _adv: Option<AdvertisingDevice> = assert_send(scan?.next()).await;
 
+    let scan: Result<_> =
+        assert_send(adapter.scan_with_filter(ScanFilter::with_services(&[btuuid::services::GENERIC_ACCESS]))).await;
+    let _adv: Option<AdvertisingDevice> = assert_send(scan?.next()).await;
+
+    let options = ScanOptions {
+        mode: ScanMode::Passive,
+        duplicates: DuplicatePolicy::ReportChanges,
+        transport: ScanTransport::Le,
+        max_pathloss: Some(60),
+    };
+    let scan: Result<_> = assert_send(adapter.scan_with_options(ScanFilter::default(), options)).await;
+    let _adv: Option<AdvertisingDevice> = assert_send(scan?.next()).await;
+
     let discovery: Result<_> = assert_send(adapter.discover_devices(&[btuuid::services::GENERIC_ACCESS])).await;
     let _device: Option<Result<Device>> = assert_send(discovery?.next()).await;
 
@@ -31,6 +64,15 @@ async fn check_adapter_apis(adapter: Adapter) -> Result<Device> {
     let events: Result<_> = assert_send(adapter.device_connection_events(&device)).await;
     let _event: Option<ConnectionEvent> = assert_send(events?.next()).await;
 
+    let events: Result<_> = assert_send(adapter.device_events()).await;
+    let _event: Option<Result<DeviceEvent>> = assert_send(events?.next()).await;
+
+    let managed = ManagedDevice::new(adapter.clone(), device.clone(), ReconnectPolicy::default());
+    let _res: Result<()> = assert_send(managed.connect()).await;
+    let mut notifications =
+        assert_send(managed.notifications(btuuid::services::BATTERY, btuuid::characteristics::BATTERY_LEVEL));
+    let _notification: Option<Result<ManagedNotification>> = assert_send(notifications.next()).await;
+
     Ok(device)
 }
 
@@ -38,12 +80,16 @@ async fn check_device_apis(device: Device) -> Result<Service> {
     let _id: DeviceId = device.id();
     let _name: Result<String> = device.name();
     let _name: Result<String> = assert_send(device.name_async()).await;
+    let _alias: Result<String> = assert_send(device.alias()).await;
     let _is_connected: bool = assert_send(device.is_connected()).await;
     let _is_paired: Result<bool> = assert_send(device.is_paired()).await;
 
-    let _pair: Result<()> = assert_send(device.pair()).await;
-    let _pair_with_agent: Result<()> = assert_send(device.pair_with_agent(&pairing::NoInputOutputPairingAgent)).await;
+    let _pair: Result<pairing::PairingResult> = assert_send(device.pair()).await;
+    let _pair_with_agent: Result<pairing::PairingResult> =
+        assert_send(device.pair_with_agent(&pairing::NoInputOutputPairingAgent)).await;
     let _unpair: Result<()> = assert_send(device.unpair()).await;
+    let _security_level: Result<SecurityLevel> = assert_send(device.security_level()).await;
+    let _encrypted: Result<bool> = assert_send(device.is_encrypted()).await;
 
     let _discovery: Result<Vec<Service>> = assert_send(device.discover_services()).await;
     let _discovery: Result<Vec<Service>> =
@@ -53,6 +99,10 @@ async fn check_device_apis(device: Device) -> Result<Service> {
     let _services_changed: Result<()> = assert_send(device.services_changed()).await;
 
     let _rssi: Result<i16> = assert_send(device.rssi()).await;
+    let _rssi: Result<i16> = assert_send(device.refresh_rssi(Duration::from_secs(1))).await;
+
+    let changes: Result<_> = assert_send(device.property_changes()).await;
+    let _change: Option<DeviceProperty> = assert_send(changes?.next()).await;
 
     Ok(services?.into_iter().next().unwrap())
 }
@@ -109,9 +159,9 @@ async fn check_descriptor_apis(descriptor: Descriptor) -> Result<()> {
 
 #[allow(unused)]
 async fn check_apis() -> Result<()> {
-    #[cfg(target_os = "android")]
+    #[cfg(all(target_os = "android", not(feature = "mock")))]
     let adapter: Result<Adapter> = unsafe { Adapter::new(core::ptr::null_mut(), core::ptr::null_mut()) };
-    #[cfg(not(target_os = "android"))]
+    #[cfg(any(not(target_os = "android"), feature = "mock"))]
     let adapter: Option<Adapter> = assert_send(Adapter::default()).await;
     let device = check_adapter_apis(adapter.unwrap()).await?;
     let service = check_device_apis(device).await?;
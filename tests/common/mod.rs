@@ -0,0 +1,61 @@
+//! Fixtures shared by the tests of the mock backend
+#![allow(dead_code)]
+
+use bluest::mock::{MockCharacteristic, MockDescriptor, MockPeripheral, MockService};
+use bluest::*;
+
+pub const SERVICE: Uuid = Uuid::from_u128(0x7e2a0000_5a1f_4c8e_9d43_0c1b2f3a4b5c);
+pub const VALUE: Uuid = Uuid::from_u128(0x7e2a0001_5a1f_4c8e_9d43_0c1b2f3a4b5c);
+pub const CONTROL: Uuid = Uuid::from_u128(0x7e2a0002_5a1f_4c8e_9d43_0c1b2f3a4b5c);
+
+/// A peripheral named "Sensor" with a readable and notifying `VALUE` and a writable `CONTROL` characteristic
+pub fn peripheral() -> MockPeripheral {
+    let mut value = CharacteristicProperties::default();
+    value.read = true;
+    value.notify = true;
+
+    let mut control = CharacteristicProperties::default();
+    control.write = true;
+
+    MockPeripheral {
+        name: Some("Sensor".to_string()),
+        adv_data: AdvertisementData {
+            services: vec![SERVICE],
+            is_connectable: true,
+            ..Default::default()
+        },
+        rssi: Some(-40),
+        services: vec![MockService {
+            uuid: SERVICE,
+            is_primary: true,
+            characteristics: vec![
+                MockCharacteristic {
+                    uuid: VALUE,
+                    properties: value,
+                    value: vec![1, 2, 3],
+                    descriptors: vec![MockDescriptor {
+                        uuid: btuuid::descriptors::CHARACTERISTIC_USER_DESCRIPTION,
+                        value: b"value".to_vec(),
+                    }],
+                },
+                MockCharacteristic {
+                    uuid: CONTROL,
+                    properties: control,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+/// Discovers the characteristic with `uuid` of the `SERVICE` of `device`
+pub async fn characteristic(device: &Device, uuid: Uuid) -> Characteristic {
+    let service = device.discover_services_with_uuid(SERVICE).await.unwrap().remove(0);
+    service
+        .discover_characteristics_with_uuid(uuid)
+        .await
+        .unwrap()
+        .remove(0)
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "mock")]
+
+use std::time::Duration;
+
+use bluest::error::ErrorKind;
+use bluest::mock::{self, MockPeripheral};
+use bluest::*;
+use futures_lite::StreamExt;
+
+mod common;
+
+use common::{characteristic, peripheral, SERVICE, VALUE};
+
+const POLICY: ReconnectPolicy = ReconnectPolicy {
+    initial_delay: Duration::from_millis(1),
+    max_delay: Duration::from_millis(4),
+    max_attempts: Some(3),
+};
+
+#[tokio::test]
+async fn resubscribes_after_reconnect() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    let managed = ManagedDevice::new(adapter, device.clone(), POLICY);
+
+    let mut notifications = managed.notifications(SERVICE, VALUE);
+    let next = notifications.next();
+    futures_lite::pin!(next);
+    assert!(futures_lite::future::poll_once(&mut next).await.is_none());
+    assert!(device.is_connected().await);
+
+    mock::notify(&characteristic(&device, VALUE).await, &[1]);
+    assert_eq!(next.await.unwrap().unwrap(), ManagedNotification::Value(vec![1]));
+
+    mock::disconnect(&device);
+    assert_eq!(
+        notifications.next().await.unwrap().unwrap(),
+        ManagedNotification::Disconnected
+    );
+    assert_eq!(
+        notifications.next().await.unwrap().unwrap(),
+        ManagedNotification::Reconnected
+    );
+    assert!(device.is_connected().await);
+
+    mock::notify(&characteristic(&device, VALUE).await, &[2]);
+    assert_eq!(
+        notifications.next().await.unwrap().unwrap(),
+        ManagedNotification::Value(vec![2])
+    );
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    let managed = ManagedDevice::new(adapter.clone(), device.clone(), POLICY);
+    managed.connect().await.unwrap();
+
+    let mut notifications = managed.notifications(SERVICE, VALUE);
+    let next = notifications.next();
+    futures_lite::pin!(next);
+    assert!(futures_lite::future::poll_once(&mut next).await.is_none());
+
+    mock::set_powered(&adapter, false);
+    assert_eq!(next.await.unwrap().unwrap(), ManagedNotification::Disconnected);
+    let err = notifications.next().await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AdapterUnavailable);
+    assert!(notifications.next().await.is_none());
+}
+
+#[tokio::test]
+async fn missing_characteristic() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, MockPeripheral::default());
+    let managed = ManagedDevice::new(adapter, device, POLICY);
+
+    let mut notifications = managed.notifications(SERVICE, VALUE);
+    let err = notifications.next().await.unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(notifications.next().await.is_none());
+}
@@ -0,0 +1,668 @@
+#![cfg(feature = "mock")]
+
+use std::time::Duration;
+
+use bluest::error::{AttError, ErrorKind};
+use bluest::mock::{self, MockPeripheral};
+use bluest::pairing::PairingAgent;
+use bluest::*;
+use futures_lite::{future, StreamExt};
+
+mod common;
+
+use common::{characteristic, peripheral, CONTROL, SERVICE, VALUE};
+
+#[tokio::test]
+async fn scan_reports_peripherals() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    let _other = mock::add_peripheral(&adapter, MockPeripheral::default());
+
+    let mut scan = adapter.scan(&[SERVICE]).await.unwrap();
+    let found = scan.next().await.unwrap();
+    assert_eq!(found.device, device);
+    assert_eq!(found.rssi, Some(-40));
+    assert_eq!(found.adv_data.services, vec![SERVICE]);
+
+    let adv_data = AdvertisementData {
+        local_name: Some("Renamed".to_string()),
+        services: vec![SERVICE],
+        ..Default::default()
+    };
+    mock::advertise(&device, adv_data.clone(), Some(-70));
+    let found = scan.next().await.unwrap();
+    assert_eq!(found.adv_data, adv_data);
+    assert_eq!(found.rssi, Some(-70));
+    assert_eq!(device.rssi().await.unwrap(), -70);
+    assert_eq!(device.refresh_rssi(Duration::from_secs(1)).await.unwrap(), -70);
+
+    let quiet = mock::add_peripheral(&adapter, MockPeripheral::default());
+    assert_eq!(quiet.rssi().await.unwrap_err().kind(), ErrorKind::NotFound);
+    let err = quiet.refresh_rssi(Duration::from_secs(1)).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn open_device_by_id() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+
+    assert_eq!(adapter.open_device(&device.id()).await.unwrap(), device);
+    assert_eq!(device.name().unwrap(), "Sensor");
+    assert_eq!(device.alias().await.unwrap(), "Sensor");
+
+    mock::remove_peripheral(&device);
+    let err = adapter.open_device(&device.id()).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn multiple_adapters() {
+    let first = Adapter::default().await.unwrap();
+    let second = Adapter::default().await.unwrap();
+    assert_ne!(first, second);
+
+    let all = Adapter::all().await.unwrap();
+    assert!(all.contains(&first));
+    assert!(all.contains(&second));
+
+    let name = second.name().unwrap();
+    assert_ne!(first.name().unwrap(), name);
+    assert_eq!(Adapter::by_name(&name).await.unwrap(), second);
+    let address = second.address().await.unwrap();
+    assert_eq!(Adapter::by_address(address).await.unwrap(), second);
+
+    assert!(second.is_powered().await.unwrap());
+    assert!(!second.alias().await.unwrap().is_empty());
+
+    drop((all, second));
+    assert_eq!(Adapter::by_name(&name).await.unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn gatt_requires_connection() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+
+    let err = device.discover_services().await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+
+    let mut events = adapter.device_connection_events(&device).await.unwrap();
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(events.next().await, Some(ConnectionEvent::Connected));
+    assert!(device.is_connected().await);
+    assert_eq!(adapter.connected_devices().await.unwrap(), vec![device.clone()]);
+
+    let value = characteristic(&device, VALUE).await;
+    mock::disconnect(&device);
+    assert_eq!(
+        events.next().await,
+        Some(ConnectionEvent::Disconnected {
+            reason: DisconnectReason::RemoteDevice
+        })
+    );
+    assert_eq!(value.read().await.unwrap_err().kind(), ErrorKind::NotConnected);
+}
+
+#[tokio::test]
+async fn disconnect_reasons() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    let mut events = adapter.device_connection_events(&device).await.unwrap();
+
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(events.next().await, Some(ConnectionEvent::Connected));
+    adapter.disconnect_device(&device).await.unwrap();
+    assert_eq!(
+        events.next().await,
+        Some(ConnectionEvent::Disconnected {
+            reason: DisconnectReason::LocalHost
+        })
+    );
+
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(events.next().await, Some(ConnectionEvent::Connected));
+    mock::disconnect_with_reason(&device, DisconnectReason::from_hci_code(0x08));
+    assert_eq!(
+        events.next().await,
+        Some(ConnectionEvent::Disconnected {
+            reason: DisconnectReason::LinkLoss
+        })
+    );
+
+    assert_eq!(DisconnectReason::from_hci_code(0x13), DisconnectReason::RemoteDevice);
+    assert_eq!(DisconnectReason::from_hci_code(0x16), DisconnectReason::LocalHost);
+    assert_eq!(DisconnectReason::from_hci_code(0x3e), DisconnectReason::Hci(0x3e));
+}
+
+#[tokio::test]
+async fn device_events() {
+    let adapter = Adapter::default().await.unwrap();
+    let mut events = adapter.device_events().await.unwrap();
+
+    let device = mock::add_peripheral(&adapter, peripheral());
+    assert_eq!(
+        events.next().await.unwrap().unwrap(),
+        DeviceEvent::Added(device.clone())
+    );
+
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(
+        events.next().await.unwrap().unwrap(),
+        DeviceEvent::Connected(device.clone())
+    );
+
+    device.pair().await.unwrap();
+    assert_eq!(
+        events.next().await.unwrap().unwrap(),
+        DeviceEvent::Paired(device.clone())
+    );
+
+    mock::set_name(&device, Some("Renamed".to_string()));
+    assert_eq!(
+        events.next().await.unwrap().unwrap(),
+        DeviceEvent::NameChanged {
+            device: device.clone(),
+            name: "Renamed".to_string()
+        }
+    );
+
+    device.unpair().await.unwrap();
+    assert_eq!(
+        events.next().await.unwrap().unwrap(),
+        DeviceEvent::Disconnected {
+            device: device.clone(),
+            reason: DisconnectReason::LocalHost
+        }
+    );
+    assert_eq!(
+        events.next().await.unwrap().unwrap(),
+        DeviceEvent::Unpaired(device.clone())
+    );
+
+    mock::remove_peripheral(&device);
+    assert_eq!(events.next().await.unwrap().unwrap(), DeviceEvent::Removed(device.id()));
+}
+
+#[tokio::test]
+async fn property_changes() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    let mut changes = device.property_changes().await.unwrap();
+
+    let manufacturer_data = ManufacturerData {
+        company_id: 0x1234,
+        data: vec![5, 6],
+    };
+    let adv_data = AdvertisementData {
+        services: vec![SERVICE],
+        manufacturer_data: Some(manufacturer_data.clone()),
+        all_manufacturer_data: vec![manufacturer_data.clone()],
+        tx_power_level: Some(4),
+        is_connectable: true,
+        ..Default::default()
+    };
+    mock::advertise(&device, adv_data.clone(), Some(-60));
+    assert_eq!(changes.next().await, Some(DeviceProperty::Rssi(-60)));
+    assert_eq!(changes.next().await, Some(DeviceProperty::TxPower(4)));
+    assert_eq!(
+        changes.next().await,
+        Some(DeviceProperty::ManufacturerData(vec![manufacturer_data]))
+    );
+
+    // Unchanged values are not reported again
+    mock::advertise(&device, adv_data, Some(-60));
+    mock::set_name(&device, Some("Renamed".to_string()));
+    assert_eq!(changes.next().await, Some(DeviceProperty::Name("Renamed".to_string())));
+
+    device.pair().await.unwrap();
+    assert_eq!(changes.next().await, Some(DeviceProperty::Paired(true)));
+}
+
+#[tokio::test]
+async fn read_write_and_notify() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    adapter.connect_device(&device).await.unwrap();
+
+    let value = characteristic(&device, VALUE).await;
+    let control = characteristic(&device, CONTROL).await;
+
+    assert_eq!(value.read().await.unwrap(), vec![1, 2, 3]);
+    assert_eq!(
+        value.write(&[0]).await.unwrap_err().kind(),
+        ErrorKind::Protocol(AttError::WRITE_NOT_PERMITTED)
+    );
+    assert_eq!(
+        control.read().await.unwrap_err().kind(),
+        ErrorKind::Protocol(AttError::READ_NOT_PERMITTED)
+    );
+    control.write(&[4, 5]).await.unwrap();
+    assert_eq!(control.value().await.unwrap(), vec![4, 5]);
+    assert_eq!(
+        control.notify().await.err().map(|x| x.kind()),
+        Some(ErrorKind::NotSupported)
+    );
+
+    let descriptor = &value.descriptors().await.unwrap()[0];
+    assert_eq!(descriptor.read().await.unwrap(), b"value");
+
+    let mut notifications = value.notify().await.unwrap();
+    assert!(value.is_notifying().await.unwrap());
+    mock::notify(&value, &[9]);
+    assert_eq!(notifications.next().await.unwrap().unwrap(), vec![9]);
+    assert_eq!(value.read().await.unwrap(), vec![9]);
+
+    mock::disconnect(&device);
+    assert!(notifications.next().await.is_none());
+}
+
+#[tokio::test]
+async fn services_changed() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    adapter.connect_device(&device).await.unwrap();
+
+    let service = device.discover_services().await.unwrap().remove(0);
+    let mut indications = device.service_changed_indications().await.unwrap();
+
+    mock::set_services(&device, Vec::new());
+    let changed = indications.next().await.unwrap().unwrap();
+    assert!(changed.was_invalidated(&service));
+    assert!(device.discover_services().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn discover_returns_connected_devices_first() {
+    let adapter = Adapter::default().await.unwrap();
+    let connected = mock::add_peripheral(&adapter, peripheral());
+    let advertising = mock::add_peripheral(&adapter, peripheral());
+    adapter.connect_device(&connected).await.unwrap();
+
+    let mut devices = adapter.discover_devices(&[SERVICE]).await.unwrap();
+    assert_eq!(devices.next().await.unwrap().unwrap(), connected);
+    assert_eq!(devices.next().await.unwrap().unwrap(), advertising);
+}
+
+#[tokio::test]
+async fn paired_and_known_devices() {
+    let adapter = Adapter::default().await.unwrap();
+    let paired = mock::add_peripheral(
+        &adapter,
+        MockPeripheral {
+            paired: true,
+            ..peripheral()
+        },
+    );
+    let unpaired = mock::add_peripheral(&adapter, peripheral());
+
+    assert_eq!(adapter.paired_devices().await.unwrap(), vec![paired.clone()]);
+    let known = adapter.known_devices().await.unwrap();
+    assert_eq!(known.len(), 2);
+    assert!(known.contains(&paired) && known.contains(&unpaired));
+
+    paired.unpair().await.unwrap();
+    assert!(adapter.paired_devices().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn power_off_ends_scan() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    adapter.connect_device(&device).await.unwrap();
+
+    let mut events = adapter.events().await.unwrap();
+    let mut scan = adapter.scan(&[]).await.unwrap();
+    mock::set_powered(&adapter, false);
+
+    assert!(scan.next().await.is_none());
+    assert_eq!(events.next().await.unwrap().unwrap(), AdapterEvent::Unavailable);
+    assert!(!device.is_connected().await);
+    assert_eq!(
+        adapter.scan(&[]).await.err().map(|x| x.kind()),
+        Some(ErrorKind::AdapterUnavailable)
+    );
+
+    mock::set_powered(&adapter, true);
+    adapter.wait_available().await.unwrap();
+}
+
+#[tokio::test]
+async fn power_and_mode_control() {
+    let adapter = Adapter::default().await.unwrap();
+
+    adapter.set_powered(false).await.unwrap();
+    assert!(!adapter.is_powered().await.unwrap());
+    let err = adapter
+        .wait_available_timeout(Duration::from_millis(10))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Timeout);
+
+    adapter.set_powered(true).await.unwrap();
+    adapter.wait_available_timeout(Duration::from_secs(10)).await.unwrap();
+
+    adapter.set_discoverable_timeout(Duration::from_secs(30)).await.unwrap();
+    adapter.set_discoverable(true).await.unwrap();
+    assert!(adapter.is_discoverable().await.unwrap());
+    adapter.set_pairable_timeout(Duration::ZERO).await.unwrap();
+    adapter.set_pairable(false).await.unwrap();
+    assert!(!adapter.is_pairable().await.unwrap());
+}
+
+#[tokio::test]
+async fn pairing() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+
+    assert!(!device.is_paired().await.unwrap());
+    let res = device
+        .pair_with_agent(&pairing::NoInputOutputPairingAgent)
+        .await
+        .unwrap();
+    assert_eq!(
+        res,
+        pairing::PairingResult {
+            bonded: true,
+            method: Some(pairing::PairingMethod::JustWorks),
+            security_level: Some(SecurityLevel::Medium),
+        }
+    );
+    assert!(device.is_paired().await.unwrap());
+    assert_eq!(device.pair().await.unwrap().method, None);
+
+    assert_eq!(
+        device.security_level().await.unwrap_err().kind(),
+        ErrorKind::NotConnected
+    );
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(device.security_level().await.unwrap(), SecurityLevel::Medium);
+    assert!(device.is_encrypted().await.unwrap());
+
+    device.unpair().await.unwrap();
+    assert!(!device.is_paired().await.unwrap());
+    adapter.connect_device(&device).await.unwrap();
+    assert_eq!(device.security_level().await.unwrap(), SecurityLevel::Low);
+    assert!(!device.is_encrypted().await.unwrap());
+}
+
+#[tokio::test]
+async fn channel_pairing_agent() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    let (agent, mut requests) = pairing::ChannelPairingAgent::new(pairing::IoCapability::DisplayYesNo);
+
+    let reject = async {
+        match requests.next().await.unwrap() {
+            pairing::PairingRequest::Confirm {
+                device: requested,
+                reply,
+            } => {
+                assert_eq!(requested, device);
+                reply.reject();
+            }
+            request => panic!("unexpected request {request:?}"),
+        }
+    };
+    let (res, ()) = future::zip(device.pair_with_agent(&agent), reject).await;
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::NotAuthorized);
+    assert!(!device.is_paired().await.unwrap());
+
+    let accept = async {
+        match requests.next().await.unwrap() {
+            pairing::PairingRequest::Confirm { reply, .. } => reply.accept(()),
+            request => panic!("unexpected request {request:?}"),
+        }
+    };
+    let (res, ()) = future::zip(device.pair_with_agent(&agent), accept).await;
+    res.unwrap();
+    assert!(device.is_paired().await.unwrap());
+
+    drop(requests);
+    device.unpair().await.unwrap();
+    assert!(device.pair_with_agent(&agent).await.is_err());
+}
+
+#[tokio::test]
+async fn fixed_passkey_agent() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    let agent = pairing::FixedPasskeyAgent::new(pairing::Passkey::new(123456));
+
+    assert_eq!(agent.request_passkey(&device).await, Ok(agent.passkey()));
+    assert!(agent
+        .confirm_passkey(&device, pairing::Passkey::new(123456))
+        .await
+        .is_ok());
+    assert!(agent
+        .confirm_passkey(&device, pairing::Passkey::new(654321))
+        .await
+        .is_err());
+
+    device.pair_with_agent(&agent).await.unwrap();
+    assert!(device.is_paired().await.unwrap());
+}
+
+#[cfg(feature = "l2cap")]
+#[tokio::test]
+async fn l2cap_channel() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    adapter.connect_device(&device).await.unwrap();
+
+    let mut listener = mock::listen_l2cap(&device, 0x80);
+    let mut channel = device.open_l2cap_channel(0x80, L2CapSecurity::NONE).await.unwrap();
+    let mut remote = listener.next().await.unwrap();
+
+    channel.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    remote.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+
+    let err = device.open_l2cap_channel(0x81, L2CapSecurity::NONE).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ConnectionFailed);
+}
+
+#[cfg(feature = "l2cap")]
+#[tokio::test]
+async fn l2cap_listener() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+
+    let listener = adapter.listen_l2cap(None, L2CapSecurity::NONE).await.unwrap();
+    let psm = listener.psm();
+    assert!((0x80..=0xff).contains(&psm));
+    let err = adapter.listen_l2cap(Some(psm), L2CapSecurity::NONE).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+
+    let err = mock::open_l2cap(&device, psm).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotConnected);
+
+    adapter.connect_device(&device).await.unwrap();
+    let mut remote = mock::open_l2cap(&device, psm).unwrap();
+    let (mut channel, peer) = listener.accept().await.unwrap();
+    assert_eq!(peer, device);
+
+    remote.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    channel.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+
+    drop(listener);
+    let err = mock::open_l2cap(&device, psm).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ConnectionFailed);
+    let listener = adapter.listen_l2cap(Some(psm), L2CapSecurity::NONE).await.unwrap();
+    assert_eq!(listener.psm(), psm);
+}
+
+#[cfg(feature = "futures-io")]
+#[tokio::test]
+async fn l2cap_channel_futures_io() {
+    use std::pin::Pin;
+
+    use futures_io::{AsyncRead, AsyncWrite};
+
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    adapter.connect_device(&device).await.unwrap();
+
+    let mut listener = mock::listen_l2cap(&device, 0x80);
+    let mut channel = device.open_l2cap_channel(0x80, L2CapSecurity::NONE).await.unwrap();
+    let (mut reader, mut writer) = listener.next().await.unwrap().split();
+
+    let len = future::poll_fn(|cx| Pin::new(&mut channel).poll_write(cx, b"ping"))
+        .await
+        .unwrap();
+    assert_eq!(len, 4);
+    let mut buf = [0; 4];
+    let len = future::poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))
+        .await
+        .unwrap();
+    assert_eq!(&buf[..len], b"ping");
+
+    future::poll_fn(|cx| Pin::new(&mut writer).poll_write(cx, b"pong"))
+        .await
+        .unwrap();
+    future::poll_fn(|cx| Pin::new(&mut writer).poll_close(cx))
+        .await
+        .unwrap();
+    let len = future::poll_fn(|cx| Pin::new(&mut channel).poll_read(cx, &mut buf))
+        .await
+        .unwrap();
+    assert_eq!(&buf[..len], b"pong");
+    let len = future::poll_fn(|cx| Pin::new(&mut channel).poll_read(cx, &mut buf))
+        .await
+        .unwrap();
+    assert_eq!(len, 0);
+}
+
+#[cfg(feature = "l2cap")]
+#[tokio::test]
+async fn l2cap_channel_metadata() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    adapter.connect_device(&device).await.unwrap();
+
+    let security = L2CapSecurity {
+        level: SecurityLevel::High,
+        key_size: 16,
+    };
+    let mut listener = mock::listen_l2cap(&device, 0x80);
+    let channel = device.open_l2cap_channel(0x80, security).await.unwrap();
+    let remote = listener.next().await.unwrap();
+
+    assert_eq!(channel.psm().unwrap(), 0x80);
+    assert_eq!(channel.peer().unwrap(), device.id());
+    assert_eq!(channel.security().unwrap(), security);
+    assert_eq!(channel.flow_control().unwrap(), L2CapFlowControl::LeCredit);
+    assert_eq!(channel.send_mtu().unwrap(), remote.recv_mtu().unwrap());
+    assert_eq!(remote.security().unwrap(), security);
+
+    let listener = adapter.listen_l2cap(None, SecurityLevel::Medium.into()).await.unwrap();
+    let _remote = mock::open_l2cap(&device, listener.psm()).unwrap();
+    let (channel, _) = listener.accept().await.unwrap();
+    assert_eq!(channel.psm().unwrap(), listener.psm());
+    assert_eq!(channel.peer().unwrap(), device.id());
+    assert_eq!(channel.security().unwrap().level, SecurityLevel::Medium);
+}
+
+#[cfg(feature = "l2cap")]
+#[tokio::test]
+async fn l2cap_channel_for_service() {
+    use bluest::mock::{MockCharacteristic, MockService};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const L2CAP_SERVICE: Uuid = Uuid::from_u128(0x7e2a1000_5a1f_4c8e_9d43_0c1b2f3a4b5c);
+
+    let mut read = CharacteristicProperties::default();
+    read.read = true;
+
+    let mut peripheral = peripheral();
+    peripheral.services.push(MockService {
+        uuid: L2CAP_SERVICE,
+        is_primary: true,
+        characteristics: vec![MockCharacteristic {
+            uuid: btuuid::descriptors::L2CAPPSM_CHARACTERISTIC,
+            properties: read,
+            value: 0x0085u16.to_le_bytes().to_vec(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral);
+    adapter.connect_device(&device).await.unwrap();
+
+    let mut listener = mock::listen_l2cap(&device, 0x85);
+    let mut channel = device
+        .open_l2cap_channel_for_service(L2CAP_SERVICE, L2CapSecurity::NONE)
+        .await
+        .unwrap();
+    assert_eq!(channel.psm().unwrap(), 0x85);
+    let mut remote = listener.next().await.unwrap();
+
+    channel.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    remote.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+
+    let err = device
+        .open_l2cap_channel_for_service(SERVICE, L2CapSecurity::NONE)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    let err = device
+        .open_l2cap_channel_for_service(VALUE, L2CapSecurity::NONE)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let psm = device
+        .discover_services_with_uuid(L2CAP_SERVICE)
+        .await
+        .unwrap()
+        .remove(0)
+        .discover_characteristics_with_uuid(btuuid::descriptors::L2CAPPSM_CHARACTERISTIC)
+        .await
+        .unwrap()
+        .remove(0);
+    mock::set_value(&psm, &[0x85]);
+    let err = device
+        .open_l2cap_channel_for_service(L2CAP_SERVICE, L2CapSecurity::NONE)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+}
+
+#[cfg(feature = "l2cap")]
+#[tokio::test]
+async fn l2cap_packet_channel() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = mock::add_peripheral(&adapter, peripheral());
+    adapter.connect_device(&device).await.unwrap();
+
+    let mut listener = mock::listen_l2cap_packets(&device, 0x80);
+    let channel = device
+        .open_l2cap_packet_channel(0x80, L2CapSecurity::NONE)
+        .await
+        .unwrap();
+    let remote = listener.next().await.unwrap();
+
+    channel.send(b"ping").await.unwrap();
+    channel.send(b"pong").await.unwrap();
+    assert_eq!(remote.recv().await.unwrap(), b"ping");
+    assert_eq!(remote.recv().await.unwrap(), b"pong");
+
+    let mtu = channel.send_mtu().unwrap();
+    assert_eq!(remote.recv_mtu().unwrap(), mtu);
+    let err = channel.send(&vec![0; mtu + 1]).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+
+    drop(remote);
+    assert_eq!(channel.recv().await.unwrap_err().kind(), ErrorKind::NotConnected);
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "mock")]
+
+use std::sync::Arc;
+
+use bluest::mock::{self, MockPeripheral};
+use bluest::*;
+use futures_lite::StreamExt;
+
+mod common;
+
+use common::SERVICE;
+
+fn advertising_device(adapter: &Adapter, adv_data: AdvertisementData, rssi: Option<i16>) -> AdvertisingDevice {
+    let device = mock::add_peripheral(
+        adapter,
+        MockPeripheral {
+            adv_data: adv_data.clone(),
+            rssi,
+            ..Default::default()
+        },
+    );
+    AdvertisingDevice { device, adv_data, rssi }
+}
+
+#[tokio::test]
+async fn filter_criteria() {
+    let adapter = Adapter::default().await.unwrap();
+    let device = advertising_device(
+        &adapter,
+        AdvertisementData {
+            local_name: Some("Sensor 42".to_string()),
+            manufacturer_data: Some(ManufacturerData {
+                company_id: 0x0059,
+                data: vec![0x12, 0x34, 0x56],
+            }),
+            all_manufacturer_data: vec![ManufacturerData {
+                company_id: 0x0059,
+                data: vec![0x12, 0x34, 0x56],
+            }],
+            services: vec![SERVICE],
+            service_data: [(btuuid::services::BATTERY, vec![0x64])].into_iter().collect(),
+            is_connectable: true,
+            ..Default::default()
+        },
+        Some(-60),
+    );
+
+    assert!(ScanFilter::default().matches(&device));
+    assert!(ScanFilter::with_services(&[SERVICE]).matches(&device));
+    assert!(!ScanFilter::with_services(&[btuuid::services::HEART_RATE]).matches(&device));
+
+    let name = |filter| ScanFilter {
+        local_name: Some(filter),
+        ..Default::default()
+    };
+    assert!(name(LocalNameFilter::Prefix("Sensor".to_string())).matches(&device));
+    assert!(name(LocalNameFilter::Exact("Sensor 42".to_string())).matches(&device));
+    assert!(!name(LocalNameFilter::Exact("Sensor".to_string())).matches(&device));
+
+    let manufacturer = |data, mask| ScanFilter {
+        manufacturer_data: Some(ManufacturerDataFilter {
+            company_id: 0x0059,
+            data,
+            mask,
+        }),
+        ..Default::default()
+    };
+    assert!(manufacturer(vec![0x12, 0x34], None).matches(&device));
+    assert!(manufacturer(vec![0x10, 0x34], Some(vec![0xf0, 0xff])).matches(&device));
+    assert!(!manufacturer(vec![0x10, 0x34], None).matches(&device));
+    assert!(!manufacturer(vec![0x12, 0x34, 0x56, 0x78], None).matches(&device));
+
+    let service_data = |uuid, data| ScanFilter {
+        service_data: Some(ServiceDataFilter { uuid, data, mask: None }),
+        ..Default::default()
+    };
+    assert!(service_data(btuuid::services::BATTERY, vec![0x64]).matches(&device));
+    assert!(!service_data(btuuid::services::BATTERY, vec![0x32]).matches(&device));
+    assert!(!service_data(SERVICE, Vec::new()).matches(&device));
+
+    let rssi = |min_rssi| ScanFilter {
+        min_rssi: Some(min_rssi),
+        ..Default::default()
+    };
+    assert!(rssi(-70).matches(&device));
+    assert!(!rssi(-50).matches(&device));
+
+    let predicate = ScanFilter {
+        predicate: Some(Arc::new(|x: &AdvertisingDevice| x.adv_data.tx_power_level.is_some())),
+        ..Default::default()
+    };
+    assert!(!predicate.matches(&device));
+}
+
+#[tokio::test]
+async fn scan_with_filter() {
+    let adapter = Adapter::default().await.unwrap();
+    let _other = advertising_device(&adapter, AdvertisementData::default(), Some(-40));
+    let near = advertising_device(
+        &adapter,
+        AdvertisementData {
+            is_connectable: true,
+            ..Default::default()
+        },
+        Some(-40),
+    );
+    let _far = advertising_device(
+        &adapter,
+        AdvertisementData {
+            is_connectable: true,
+            ..Default::default()
+        },
+        Some(-90),
+    );
+
+    let mut scan = adapter
+        .scan_with_filter(ScanFilter {
+            min_rssi: Some(-60),
+            connectable_only: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(scan.next().await.unwrap().device, near.device);
+}
+
+#[tokio::test]
+async fn scan_with_options() {
+    let adapter = Adapter::default().await.unwrap();
+    let adv_data = AdvertisementData {
+        tx_power_level: Some(0),
+        ..Default::default()
+    };
+    let near = advertising_device(&adapter, adv_data.clone(), Some(-40));
+    let _far = advertising_device(&adapter, adv_data.clone(), Some(-90));
+    let _unknown_tx_power = advertising_device(&adapter, AdvertisementData::default(), Some(-40));
+
+    let options = ScanOptions {
+        duplicates: DuplicatePolicy::ReportChanges,
+        max_pathloss: Some(60),
+        ..Default::default()
+    };
+    let mut scan = adapter.scan_with_options(ScanFilter::default(), options).await.unwrap();
+    assert_eq!(scan.next().await.unwrap().device, near.device);
+
+    // An unchanged advertisement is not reported again
+    mock::advertise(&near.device, adv_data, Some(-45));
+    let changed = AdvertisementData {
+        local_name: Some("Sensor".to_string()),
+        tx_power_level: Some(0),
+        ..Default::default()
+    };
+    mock::advertise(&near.device, changed.clone(), Some(-45));
+    assert_eq!(scan.next().await.unwrap().adv_data, changed);
+}